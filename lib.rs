@@ -0,0 +1,3886 @@
+//! Entropy-driven Wordle solver.
+//!
+//! The solver keeps a prior over the dictionary, narrows the set of possible answers from
+//! match feedback and suggests the next guess according to a [`Policy`]. Filesystem helpers
+//! (dictionary loading, caches, training histograms) are unavailable on `wasm32`, where the
+//! `wasm` feature exposes browser bindings instead.
+
+#[cfg(not(target_arch = "wasm32"))]
+use glob::glob;
+use serde::{Deserialize, Serialize};
+#[cfg(not(target_arch = "wasm32"))]
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::collections::HashSet;
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::{self, BufRead, BufWriter, Write};
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::{f64, fmt};
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+/// How much the solver (and the tool around it) prints besides its results
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub enum Verbosity {
+    Quiet,   // Results and errors only
+    Normal,  // Plus progress, "Loaded …" notes and warnings
+    Verbose, // Plus the settings in use and per-step timing
+}
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+/// Set the process-wide verbosity (`Verbosity::Normal` by default)
+pub fn set_verbosity(level: Verbosity) {
+    VERBOSITY.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn verbosity() -> Verbosity {
+    match VERBOSITY.load(Ordering::Relaxed) {
+        0 => Verbosity::Quiet,
+        1 => Verbosity::Normal,
+        _ => Verbosity::Verbose,
+    }
+}
+
+/// `println!` unless the verbosity is `Quiet`
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if $crate::verbosity() > $crate::Verbosity::Quiet {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// `eprintln!` unless the verbosity is `Quiet`, for warnings and progress
+#[macro_export]
+macro_rules! note {
+    ($($arg:tt)*) => {
+        if $crate::verbosity() > $crate::Verbosity::Quiet {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+/// `eprintln!` only when the verbosity is `Verbose`
+#[macro_export]
+macro_rules! verbose {
+    ($($arg:tt)*) => {
+        if $crate::verbosity() == $crate::Verbosity::Verbose {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+// Split the raw contents of a 5 letter words dictionary into words, one per line. Anything
+// after a `#` is a comment; surrounding whitespace is trimmed and blank lines are skipped.
+#[cfg(not(target_arch = "wasm32"))]
+fn parse_dictionary(contents: &[u8]) -> Vec<String> {
+    contents
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| dictionary_word(&line).map(str::to_string))
+        .collect()
+}
+
+// The word on one dictionary line, without its comment or surrounding whitespace
+#[cfg(not(target_arch = "wasm32"))]
+fn dictionary_word(line: &str) -> Option<&str> {
+    let word = line.split('#').next().unwrap_or_default().trim();
+    (!word.is_empty()).then_some(word)
+}
+
+/// Encode a dictionary read line by line, keeping only the encodings: the words are never
+/// held as strings, so very large lists need little more memory than their encodings.
+/// Lines that aren't a valid word in `alphabet` (or aren't UTF-8) are skipped; returns the
+/// encodings, the number of lines skipped and a description of the first one.
+#[cfg(not(target_arch = "wasm32"))]
+fn stream_word_encodings<R: BufRead>(
+    mut reader: R,
+    alphabet: &Alphabet,
+) -> io::Result<(Vec<WordEncoding>, usize, Option<String>)> {
+    let mut encodings = Vec::new();
+    let mut skipped = 0;
+    let mut first_skipped = None;
+    let mut line = Vec::new();
+    for number in 1.. {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        let encoded = match std::str::from_utf8(&line) {
+            Ok(text) => match dictionary_word(text) {
+                Some(word) => WordEncoding::from_string(word, alphabet),
+                None => continue,
+            },
+            Err(_) => Err("not valid UTF-8".to_string()),
+        };
+        match encoded {
+            Ok(encoding) => encodings.push(encoding),
+            Err(e) => {
+                skipped += 1;
+                first_skipped.get_or_insert_with(|| format!("line {number}: {e}"));
+            }
+        }
+    }
+    Ok((encodings, skipped, first_skipped))
+}
+
+/// 64-bit FNV-1a hasher. Unlike `DefaultHasher` its output is fixed across runs and Rust
+/// versions, so it can key on-disk caches.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1a {
+    // Hash `letters` as a little-endian u32 count followed by each letter as a little-endian
+    // u32, so the bytes are the same whatever the platform's word size and endianness
+    fn write_letters(&mut self, letters: &[char]) {
+        self.write(&(letters.len() as u32).to_le_bytes());
+        for c in letters {
+            self.write(&u32::from(*c).to_le_bytes());
+        }
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= *b as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Order-sensitive fingerprint of a word list: an FNV-1a hash of each word's letters,
+/// normalised as by `WordEncoding::from_string`. It is fixed across runs, so caches and saved
+/// states can check they were made for the same dictionary; reordering the list changes it,
+/// since the prior depends on the order. A solver's dictionary fingerprints the same
+/// whether hashed from its words or its encodings, and on every platform, wasm included.
+pub fn dictionary_fingerprint(words: &[String]) -> u64 {
+    let mut hasher = Fnv1a::default();
+    for word in words {
+        let letters: Vec<char> = word.chars().map(Alphabet::normalise).collect();
+        hasher.write_letters(&letters);
+    }
+    hasher.finish()
+}
+
+// `dictionary_fingerprint` of the words `encodings` spell, left open to hash more into
+fn fingerprint_hasher(encodings: &[WordEncoding]) -> Fnv1a {
+    let mut hasher = Fnv1a::default();
+    for word in encodings {
+        hasher.write_letters(&word.positions);
+    }
+    hasher
+}
+
+/// FNV-1a hash of the raw dictionary contents, read in chunks from `reader`, and the
+/// alphabet they are encoded with
+#[cfg(not(target_arch = "wasm32"))]
+fn content_hash<R: io::Read>(mut reader: R, alphabet: &Alphabet) -> io::Result<u64> {
+    let mut hasher = Fnv1a::default();
+    let mut chunk = vec![0u8; 64 * 1024];
+    loop {
+        match reader.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(n) => hasher.write(&chunk[..n]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    hasher.write_letters(&alphabet.letters);
+    Ok(hasher.finish())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn encoding_cache_path(cache_dir: &Path, dictionary_hash: u64) -> PathBuf {
+    cache_dir.join(format!("encodings.{:016x}.bin", dictionary_hash))
+}
+
+/// Load cached encodings for the dictionary with the given hash, if a valid cache exists:
+/// one for the same contents whose encodings still have the fingerprint they were saved with.
+#[cfg(not(target_arch = "wasm32"))]
+fn load_encoding_cache(cache_dir: &Path, dictionary_hash: u64) -> Option<Vec<WordEncoding>> {
+    let file = File::open(encoding_cache_path(cache_dir, dictionary_hash)).ok()?;
+    // Cache layout: (dictionary_hash, fingerprint of the encodings, encodings)
+    let (hash, fingerprint, encodings): (u64, u64, Vec<WordEncoding>) =
+        bincode::deserialize_from(io::BufReader::new(file)).ok()?;
+    (hash == dictionary_hash && fingerprint == fingerprint_hasher(&encodings).finish())
+        .then_some(encodings)
+}
+
+/// Write the encodings cache
+#[cfg(not(target_arch = "wasm32"))]
+fn save_encoding_cache(
+    cache_dir: &Path,
+    dictionary_hash: u64,
+    encodings: &[WordEncoding],
+) -> io::Result<()> {
+    write_cache_file(
+        cache_dir,
+        &encoding_cache_path(cache_dir, dictionary_hash),
+        &(
+            dictionary_hash,
+            fingerprint_hasher(encodings).finish(),
+            encodings,
+        ),
+    )
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn opener_cache_path(cache_dir: &Path, opener_key: u64) -> PathBuf {
+    cache_dir.join(format!("opener.{:016x}.bin", opener_key))
+}
+
+/// Load the cached opening guess (a dictionary index) for the given opener key
+#[cfg(not(target_arch = "wasm32"))]
+fn load_opener_cache(cache_dir: &Path, opener_key: u64) -> Option<usize> {
+    let file = File::open(opener_cache_path(cache_dir, opener_key)).ok()?;
+    // Cache layout: (opener_key, dictionary index of the opener)
+    let (key, index): (u64, usize) = bincode::deserialize_from(io::BufReader::new(file)).ok()?;
+    (key == opener_key).then_some(index)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_opener_cache(cache_dir: &Path, opener_key: u64, index: usize) -> io::Result<()> {
+    write_cache_file(
+        cache_dir,
+        &opener_cache_path(cache_dir, opener_key),
+        &(opener_key, index),
+    )
+}
+
+/// Serialize `value` to `path`. The file is written under a temporary name and renamed into
+/// place, so concurrent workers never observe a half-written cache.
+#[cfg(not(target_arch = "wasm32"))]
+fn write_cache_file<T: Serialize>(cache_dir: &Path, path: &Path, value: &T) -> io::Result<()> {
+    std::fs::create_dir_all(cache_dir)?;
+    let tmp_path = path.with_extension(format!("tmp.{}", std::process::id()));
+
+    let mut writer = BufWriter::new(File::create(&tmp_path)?);
+    bincode::serialize_into(&mut writer, value).map_err(io::Error::other)?;
+    writer.flush()?;
+    drop(writer);
+
+    std::fs::rename(tmp_path, path)
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum MatchKind {
+    NoMatch,
+    Partial,
+    Match,
+}
+
+impl fmt::Display for MatchKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            MatchKind::NoMatch => "NoMatch",
+            MatchKind::Partial => "Partial",
+            MatchKind::Match => "Match",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+// Declare a custom match result
+pub type MatchResult = [MatchKind; 5];
+
+/// A match result packed into one byte: the base-3 number whose digit `i` (least
+/// significant first) is the kind at position `i`, with NoMatch = 0, Partial = 1 and
+/// Match = 2. There are 243 patterns, so a pattern also indexes a pattern distribution.
+pub type Pattern = u8;
+
+// The pattern of a correct guess, MMMMM
+const SOLVED_PATTERN: Pattern = 242;
+
+/// Pack a match result into its `Pattern`
+pub fn pack_pattern(result: &MatchResult) -> Pattern {
+    result.iter().rev().fold(0, |pattern, kind| {
+        pattern * 3
+            + match kind {
+                MatchKind::NoMatch => 0,
+                MatchKind::Partial => 1,
+                MatchKind::Match => 2,
+            }
+    })
+}
+
+/// The match result packed in `pattern` (which must be below 243)
+pub fn unpack_pattern(mut pattern: Pattern) -> MatchResult {
+    let mut result = [MatchKind::NoMatch; 5];
+    for kind in result.iter_mut() {
+        *kind = match pattern % 3 {
+            0 => MatchKind::NoMatch,
+            1 => MatchKind::Partial,
+            _ => MatchKind::Match,
+        };
+        pattern /= 3;
+    }
+    result
+}
+
+/// The index in 0..243 of `result` in pattern distributions: its `Pattern` widened to
+/// `usize`. Position 1 is the least significant base-3 digit, with grey = 0, yellow = 1 and
+/// green = 2, the same encoding as 3Blue1Brown's Wordle pattern matrices.
+pub fn pattern_to_index(result: MatchResult) -> usize {
+    pack_pattern(&result) as usize
+}
+
+/// The match result at `index` of a pattern distribution; the inverse of `pattern_to_index`.
+/// Panics unless `index` is below 243.
+pub fn index_to_pattern(index: usize) -> MatchResult {
+    assert!(index < 243, "pattern index {index} out of range (0..243)");
+    unpack_pattern(index as Pattern)
+}
+
+/// The unit entropies are reported in, i.e. the base of the logarithm. The solver itself
+/// always works in bits; this only changes what is shown and what shards record.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntropyUnit {
+    #[default]
+    Bits, // log2
+    Nats,   // ln
+    Digits, // log10 (also called hartleys or bans)
+}
+
+impl EntropyUnit {
+    /// Logarithm of `x` in this unit's base
+    pub fn log(self, x: f64) -> f64 {
+        match self {
+            EntropyUnit::Bits => x.log2(),
+            EntropyUnit::Nats => x.ln(),
+            EntropyUnit::Digits => x.log10(),
+        }
+    }
+
+    /// `bits` of entropy expressed in this unit
+    pub fn from_bits(self, bits: f64) -> f64 {
+        bits * self.log(2.0)
+    }
+
+    /// An entropy in this unit expressed in bits
+    pub fn to_bits(self, value: f64) -> f64 {
+        value / self.log(2.0)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EntropyUnit::Bits => "bits",
+            EntropyUnit::Nats => "nats",
+            EntropyUnit::Digits => "digits",
+        }
+    }
+
+    /// First line of a shard whose entropies are in this unit. Bits keep the plain
+    /// `SHARD_HEADER`, so shards written before units existed still read as bits.
+    /// Shards from before the `solved` column are recognised without it.
+    pub fn shard_header(self) -> String {
+        match self {
+            EntropyUnit::Bits => SHARD_HEADER.to_string(),
+            unit => SHARD_HEADER.replace("entropy", &format!("entropy_{}", unit.label())),
+        }
+    }
+
+    // The unit of a shard with first line `header`, if it is a shard header
+    #[cfg(not(target_arch = "wasm32"))]
+    fn of_shard_header(header: &str) -> Option<EntropyUnit> {
+        [EntropyUnit::Bits, EntropyUnit::Nats, EntropyUnit::Digits]
+            .into_iter()
+            .find(|unit| header == unit.shard_header())
+            .or_else(|| EntropyUnit::of_legacy_shard_header(header))
+    }
+
+    // The unit of a shard with first line `header`, if it is a header from before the
+    // `solved` column
+    #[cfg(not(target_arch = "wasm32"))]
+    fn of_legacy_shard_header(header: &str) -> Option<EntropyUnit> {
+        [EntropyUnit::Bits, EntropyUnit::Nats, EntropyUnit::Digits]
+            .into_iter()
+            .find(|unit| Some(header) == unit.shard_header().strip_suffix(",solved"))
+    }
+}
+
+impl fmt::Display for EntropyUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl std::str::FromStr for EntropyUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<EntropyUnit, String> {
+        match s.to_lowercase().as_str() {
+            "bits" => Ok(EntropyUnit::Bits),
+            "nats" => Ok(EntropyUnit::Nats),
+            "digits" => Ok(EntropyUnit::Digits),
+            _ => Err(format!(
+                "unknown entropy unit '{s}' (expected bits, nats or digits)"
+            )),
+        }
+    }
+}
+
+/// Whether `result` is the all-green feedback of a correct guess
+pub fn is_solved(result: &MatchResult) -> bool {
+    result.iter().all(|kind| *kind == MatchKind::Match)
+}
+
+/// Where the feedback for a guess comes from: a known secret, a player at the keyboard, or
+/// anything else that can score a guess, such as a Wordle server. Drive a solver with it
+/// through `WordleSolver::guess_with`. Closures `Fn(&WordEncoding) -> MatchResult` are
+/// oracles too.
+pub trait Oracle {
+    fn check(&self, guess: &WordEncoding) -> MatchResult;
+}
+
+impl<F: Fn(&WordEncoding) -> MatchResult> Oracle for F {
+    fn check(&self, guess: &WordEncoding) -> MatchResult {
+        self(guess)
+    }
+}
+
+/// Scores guesses against a secret the caller knows, as in self-play and benchmarks
+pub struct FixedSecret(pub WordEncoding);
+
+impl Oracle for FixedSecret {
+    fn check(&self, guess: &WordEncoding) -> MatchResult {
+        guess.match_result(&self.0)
+    }
+}
+
+/// Asks for the feedback on standard input, in any scheme `parse_feedback` reads, until
+/// it gets a valid line. Panics if standard input ends first.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct StdinOracle;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Oracle for StdinOracle {
+    fn check(&self, guess: &WordEncoding) -> MatchResult {
+        let stdin = io::stdin();
+        loop {
+            print!("Feedback for {} (e.g. MPNPN): ", guess);
+            io::stdout().flush().ok();
+            let mut line = String::new();
+            match stdin.lock().read_line(&mut line) {
+                Ok(0) => panic!(
+                    "standard input ended before feedback for {} was entered",
+                    guess
+                ),
+                Ok(_) => match parse_feedback(&line.trim().to_uppercase()) {
+                    Ok(feedback) => return feedback,
+                    Err(e) => eprintln!("{}, please re-enter.", e),
+                },
+                Err(e) => panic!("cannot read feedback from standard input: {e}"),
+            }
+        }
+    }
+}
+
+/// The ordered set of letters words are spelled with. Letters are stored upper case and
+/// looked up through an index table, so any alphabet (e.g. Spanish with Ñ) works.
+#[derive(Clone, Debug)]
+pub struct Alphabet {
+    letters: Vec<char>,
+    index: HashMap<char, usize>,
+}
+
+impl Alphabet {
+    /// Build an alphabet from its letters in order, e.g. "ABCDEFGHIJKLMNÑOPQRSTUVWXYZ"
+    pub fn new(letters: &str) -> Result<Alphabet, String> {
+        let mut alphabet = Alphabet {
+            letters: Vec::new(),
+            index: HashMap::new(),
+        };
+        for c in letters.chars().map(Alphabet::normalise) {
+            if !c.is_alphabetic() {
+                return Err(format!("'{}' is not a letter", c));
+            }
+            if alphabet.index.insert(c, alphabet.letters.len()).is_some() {
+                return Err(format!("letter '{}' appears twice in the alphabet", c));
+            }
+            alphabet.letters.push(c);
+        }
+        if alphabet.letters.is_empty() {
+            return Err("the alphabet has no letters".to_string());
+        }
+        Ok(alphabet)
+    }
+
+    /// A to Z
+    pub fn english() -> Alphabet {
+        Alphabet::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ").unwrap()
+    }
+
+    pub fn letters(&self) -> &[char] {
+        &self.letters
+    }
+
+    pub fn len(&self) -> usize {
+        self.letters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.letters.is_empty()
+    }
+
+    /// Position of `c` (in any case) in the alphabet
+    pub fn index(&self, c: char) -> Option<usize> {
+        self.index.get(&Alphabet::normalise(c)).copied()
+    }
+
+    /// Upper case form of `c`, as letters are stored
+    pub fn normalise(c: char) -> char {
+        let mut upper = c.to_uppercase();
+        match (upper.next(), upper.next()) {
+            (Some(u), None) => u,
+            _ => c, // e.g. ß, whose upper case is two letters
+        }
+    }
+}
+
+impl Default for Alphabet {
+    fn default() -> Self {
+        Alphabet::english()
+    }
+}
+
+impl PartialEq for Alphabet {
+    fn eq(&self, other: &Self) -> bool {
+        self.letters == other.letters
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WordEncoding {
+    pub positions: [char; 5], // Encode symbol position
+    pub frequencies: Vec<u8>, // Encode symbol frequency, indexed like the alphabet
+}
+
+impl WordEncoding {
+    /// Encode a five letter word, failing if it uses a letter outside `alphabet`
+    pub fn from_string(word: &str, alphabet: &Alphabet) -> Result<WordEncoding, String> {
+        if word.chars().count() != 5 {
+            return Err(format!("'{}' is not a five-letter word", word));
+        }
+
+        let mut positions = [' '; 5];
+        let mut frequencies = vec![0u8; alphabet.len()];
+        for (i, c) in word.chars().enumerate() {
+            let Some(idx) = alphabet.index(c) else {
+                return Err(format!(
+                    "'{}' contains '{}', which is not in the alphabet",
+                    word, c
+                ));
+            };
+            positions[i] = alphabet.letters[idx];
+            frequencies[idx] += 1;
+        }
+
+        Ok(WordEncoding {
+            positions,
+            frequencies,
+        })
+    }
+
+    pub fn match_result(&self, other: &WordEncoding) -> MatchResult {
+        unpack_pattern(self.match_pattern(other))
+    }
+
+    /// `match_result` in packed form, computed directly
+    pub fn match_pattern(&self, other: &WordEncoding) -> Pattern {
+        let mut digits = [0u8; 5]; // Base-3 digit of each position, see `Pattern`
+        let mut used = [false; 5]; // Letters of `other` already accounted for
+
+        for i in 0..5 {
+            if self.positions[i] == other.positions[i] {
+                digits[i] = 2;
+                used[i] = true;
+            }
+        }
+
+        for (digit, c) in digits.iter_mut().zip(self.positions.iter()) {
+            if *digit == 0
+                && let Some(j) = (0..5).find(|&j| !used[j] && other.positions[j] == *c)
+            {
+                *digit = 1;
+                used[j] = true;
+            }
+        }
+
+        digits
+            .iter()
+            .rev()
+            .fold(0, |pattern, digit| pattern * 3 + digit)
+    }
+
+    /// Number of times `c` occurs in the word
+    pub fn count(&self, c: char) -> u8 {
+        self.positions.iter().filter(|p| **p == c).count() as u8
+    }
+
+    /// The letters occurring more than once in the word, with how often, in alphabet order.
+    /// `alphabet` must be the one the word was encoded with.
+    pub fn repeated_letters(&self, alphabet: &Alphabet) -> Vec<(char, u8)> {
+        alphabet
+            .letters
+            .iter()
+            .zip(&self.frequencies)
+            .filter(|(_, count)| **count > 1)
+            .map(|(c, count)| (*c, *count))
+            .collect()
+    }
+
+    /// Whether the word has each letter at least `required[i]` times, in any position, and
+    /// none of the letters with `excluded[i]` set. Both are indexed like the alphabet, as
+    /// `frequencies` is.
+    pub fn contains_letters(&self, required: &[u8], excluded: &[bool]) -> bool {
+        self.frequencies
+            .iter()
+            .zip(required.iter().zip(excluded.iter()))
+            .all(|(have, (need, banned))| have >= need && !(*banned && *have > 0))
+    }
+}
+
+impl fmt::Display for WordEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s: String = self.positions.iter().collect();
+        write!(f, "{}", s)
+    }
+}
+
+/// Dictionary words sorted for lookup by prefix, e.g. to expand an abbreviated guess
+#[derive(Clone, Debug)]
+pub struct PrefixIndex {
+    words: Vec<(String, usize)>, // (word, dictionary index), sorted by word
+}
+
+impl PrefixIndex {
+    pub fn new(dictionary: &[WordEncoding]) -> PrefixIndex {
+        let mut words: Vec<(String, usize)> = dictionary
+            .iter()
+            .enumerate()
+            .map(|(i, word)| (word.to_string(), i))
+            .collect();
+        words.sort();
+        PrefixIndex { words }
+    }
+
+    /// Every `(word, dictionary index)` starting with `prefix`, alphabetically. The prefix is
+    /// normalised like the letters of `WordEncoding::from_string`.
+    pub fn matches(&self, prefix: &str) -> &[(String, usize)] {
+        let prefix: String = prefix.chars().map(Alphabet::normalise).collect();
+        let start = self.words.partition_point(|(word, _)| *word < prefix);
+        let len = self.words[start..].partition_point(|(word, _)| word.starts_with(&prefix));
+        &self.words[start..start + len]
+    }
+}
+
+// Base weight given to words that are absent from (or have zero count in) a frequency file
+pub const FREQUENCY_FLOOR_WEIGHT: f64 = 1e-3;
+
+// Factor the base weight of a word missing from the answers list is multiplied by
+pub const NON_ANSWER_WEIGHT: f64 = 1e-4;
+
+// Guesses allowed in a standard game of Wordle
+pub const DEFAULT_MAX_GUESSES: usize = 6;
+
+// Dictionary rank at which the rank prior's sigmoid falls to half weight
+pub const RANK_PRIOR_MIDPOINT: f64 = 1500.0;
+
+// Steepness of the rank prior's sigmoid, per rank
+pub const RANK_PRIOR_STEEPNESS: f64 = 0.05;
+
+// Slope of the untrained expected-moves model, see `estimate_expected_moves`
+pub const UNTRAINED_MOVES_SLOPE: f64 = 0.55;
+
+// Extra expected moves charged per move the estimate runs past the remaining guess budget
+pub const BUDGET_OVERRUN_PENALTY: f64 = 10.0;
+
+// How far (in bits) below the current best a guess' entropy from the previous step may be
+// before `step()` skips re-evaluating it
+pub const ENTROPY_PRUNE_MARGIN: f64 = 0.25;
+
+// With `lookahead` 2, the depth-2 search runs once at most this many possibilities remain
+pub const LOOKAHEAD_THRESHOLD: usize = 15;
+
+// Number of top one-ply guesses (besides the remaining possibilities) the depth-2 search tries
+pub const LOOKAHEAD_CANDIDATES: usize = 10;
+
+// With this many possibilities or fewer, `step()` checks whether guessing one of them is
+// already optimal before scanning the whole dictionary
+pub const ENDGAME_THRESHOLD: usize = 20;
+
+// Under `MinimizeScore`, `step()` guesses a possibility outright once its prior exceeds this
+pub const COMMIT_THRESHOLD: f64 = 0.99;
+
+// Objectives closer than this are considered tied when ranking guesses in `step()`
+pub const TIE_EPSILON: f64 = 1e-9;
+
+#[derive(PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum Policy {
+    MaximizeEntropy,
+    MinimizeScore,
+    MinimizeWorstCase, // Minimise the largest group of words any feedback could leave
+    Blend { lambda: f64 }, // Maximise entropy - lambda * expected score, to trade between the two
+}
+
+/// Facts about the answer known without a guess, e.g. "position 3 is E, A and T are in the
+/// word, R is not"
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct Constraints {
+    pub greens: [Option<char>; 5], // Letter known to be at each position
+    pub present: Vec<(char, u8)>,  // Letters in the word, with the minimum number of occurrences
+    pub absent: Vec<char>,         // Letters not in the word at all
+}
+
+impl Constraints {
+    pub fn matches(&self, word: &WordEncoding) -> bool {
+        let greens_ok = self
+            .greens
+            .iter()
+            .zip(word.positions.iter())
+            .all(|(green, c)| green.is_none_or(|g| g == *c));
+        let present_ok = self.present.iter().all(|(c, n)| word.count(*c) >= *n);
+        let absent_ok = self.absent.iter().all(|c| word.count(*c) == 0);
+
+        greens_ok && present_ok && absent_ok
+    }
+}
+
+/// Why `step()` could not produce a guess
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum SolverError {
+    NoCandidates,       // No dictionary word is consistent with the feedback so far
+    Exhausted,          // Every word that could be guessed has been guessed already
+    Config(String),     // A `SolverConfig` could not be built (unreadable file, bad word, …)
+    DictionaryMismatch, // Two solvers being combined don't share the same dictionary
+}
+
+impl fmt::Display for SolverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolverError::NoCandidates => write!(f, "no words match the feedback so far"),
+            SolverError::Exhausted => write!(f, "no guesses left to try"),
+            SolverError::Config(message) => write!(f, "{message}"),
+            SolverError::DictionaryMismatch => write!(f, "the solvers use different dictionaries"),
+        }
+    }
+}
+
+impl std::error::Error for SolverError {}
+
+// How a guess scores on each policy's objective, as compared by `step()`
+#[derive(Clone, Copy)]
+struct Rating {
+    entropy: f64,
+    expected_score: f64,
+    worst_case: usize,
+}
+
+/// The guess `best_guess()` would play and how it rates against the current possibilities
+#[derive(Clone, Debug)]
+pub struct GuessInfo {
+    pub word: WordEncoding,
+    pub entropy: f64,
+    pub expected_score: f64, // f64::INFINITY under policies that don't score guesses
+    pub worst_case: usize,   // Most possibilities any feedback to the guess leaves
+    pub runner_up: Option<(WordEncoding, f64)>, // Second best guess and its entropy; None for the start word, a cached opener or a lookahead pick
+    match_results: Vec<(Pattern, f64)>,
+    match_pattern_pd: [f64; 243],
+}
+
+/// One guess of a game played by `WordleSolver::play_out`
+#[derive(Clone, Debug)]
+pub struct TranscriptTurn {
+    pub guess: WordEncoding,
+    pub pattern: MatchResult, // Feedback the guess got from the secret
+    pub remaining: usize,     // Possibilities left when the guess was chosen
+    pub entropy: f64,         // Expected bits the guess would gain, as rated by `step()`
+}
+
+/// A game played to the end by `WordleSolver::play_out`
+#[derive(Clone, Debug)]
+pub struct GameTranscript {
+    pub turns: Vec<TranscriptTurn>,
+    pub solved: bool,
+    pub error: Option<SolverError>, // Why the game stopped early, if it did; None when solved or out of guesses
+}
+
+impl GameTranscript {
+    /// Number of guesses played
+    pub fn guesses(&self) -> usize {
+        self.turns.len()
+    }
+}
+
+/// Why `step()` chose its guess, as reported by `WordleSolver::explain`
+#[derive(Clone, Debug)]
+pub struct GuessExplanation {
+    pub guess: String,
+    pub patterns: usize, // distinct feedback patterns the guess splits the possibilities into
+    pub largest_bucket: usize, // most possibilities any one of those patterns leaves
+    pub answer_probability: f64, // prior of the guess itself being the answer (0 if it cannot be)
+    pub entropy: f64,
+    pub runner_up: Option<(String, f64)>, // second best guess and its entropy; None for a cached opener or a guess not chosen by step()
+}
+
+/// A snapshot of a game in progress, taken by `WordleSolver::save_state`. The dictionary is
+/// left out; `load_state` checks that the snapshot was taken with the same one.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SolverState {
+    dictionary_hash: u64,
+    policy: Policy,
+    max_guesses: usize,
+    start_word: Option<WordEncoding>,
+    base_weights: Vec<f64>,
+    exact: bool,
+    lookahead: usize,
+    hard_mode: bool,
+    #[serde(default)]
+    guess_threshold: f64,
+    #[serde(default = "default_commit_threshold")]
+    commit_threshold: f64,
+    previous_guesses: Vec<WordEncoding>,
+    #[serde(default)]
+    information: Vec<(f64, f64)>,
+    current_possibilities: Vec<usize>,
+    prior: Vec<f64>,
+    current_guess: Option<WordEncoding>,
+}
+
+// `commit_threshold` of a snapshot from before it was saved
+fn default_commit_threshold() -> f64 {
+    COMMIT_THRESHOLD
+}
+
+/// Where a `SolverConfig` reads its words from
+#[derive(Clone, Debug)]
+pub enum DictionarySource {
+    #[cfg(not(target_arch = "wasm32"))]
+    File(PathBuf), // One word per line, most common first
+    Words(Vec<String>), // In-memory list, most common first
+}
+
+/// What the prior over possible answers is proportional to
+#[derive(Clone, Debug)]
+pub enum PriorConfig {
+    Rank, // A sigmoid of dictionary rank (the default)
+    #[cfg(not(target_arch = "wasm32"))]
+    Frequencies(PathBuf), // `word,count` file, see `WordleSolver::load_frequencies`
+    #[cfg(not(target_arch = "wasm32"))]
+    Zipf(PathBuf), // `word,zipf` file, see `WordleSolver::load_zipf`
+    #[cfg(not(target_arch = "wasm32"))]
+    Weights(PathBuf, f64), // `word,weight` file and the weight of missing words
+}
+
+/// Everything needed to build a `WordleSolver`. Start from `from_file` or `from_words`,
+/// override what differs from the defaults (English alphabet, `MaximizeEntropy`, rank prior,
+/// `DEFAULT_MAX_GUESSES`, no cache), then `build()`.
+#[derive(Clone, Debug)]
+pub struct SolverConfig {
+    dictionary: DictionarySource,
+    alphabet: Alphabet,
+    policy: Policy,
+    expected_moves_curve: Vec<Bucket>,
+    prior: PriorConfig,
+    #[cfg(not(target_arch = "wasm32"))]
+    answers: Option<PathBuf>,
+    hard_mode: bool,
+    guess_threshold: f64,
+    commit_threshold: f64,
+    entropy_unit: EntropyUnit,
+    max_guesses: usize,
+    start_word: Option<String>,
+    exact: bool,
+    lookahead: usize,
+    #[cfg(not(target_arch = "wasm32"))]
+    cache_dir: Option<PathBuf>,
+}
+
+impl SolverConfig {
+    fn new(dictionary: DictionarySource) -> SolverConfig {
+        SolverConfig {
+            dictionary,
+            alphabet: Alphabet::english(),
+            policy: Policy::MaximizeEntropy,
+            expected_moves_curve: Vec::new(),
+            prior: PriorConfig::Rank,
+            #[cfg(not(target_arch = "wasm32"))]
+            answers: None,
+            hard_mode: false,
+            guess_threshold: 0.0,
+            commit_threshold: COMMIT_THRESHOLD,
+            entropy_unit: EntropyUnit::Bits,
+            max_guesses: DEFAULT_MAX_GUESSES,
+            start_word: None,
+            exact: false,
+            lookahead: 1,
+            #[cfg(not(target_arch = "wasm32"))]
+            cache_dir: None,
+        }
+    }
+
+    /// Read the dictionary from a file of one word per line, most common first
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_file<P: Into<PathBuf>>(path: P) -> SolverConfig {
+        SolverConfig::new(DictionarySource::File(path.into()))
+    }
+
+    /// Use an in-memory word list, most common first
+    pub fn from_words(words: Vec<String>) -> SolverConfig {
+        SolverConfig::new(DictionarySource::Words(words))
+    }
+
+    /// Letters the dictionary is spelled with
+    pub fn alphabet(mut self, alphabet: Alphabet) -> SolverConfig {
+        self.alphabet = alphabet;
+        self
+    }
+
+    pub fn policy(mut self, policy: Policy) -> SolverConfig {
+        self.policy = policy;
+        self
+    }
+
+    /// Entropy → expected moves curve from training, used by `MinimizeScore` and `Blend`
+    pub fn expected_moves_curve(mut self, curve: Vec<Bucket>) -> SolverConfig {
+        self.expected_moves_curve = curve;
+        self
+    }
+
+    pub fn prior(mut self, prior: PriorConfig) -> SolverConfig {
+        self.prior = prior;
+        self
+    }
+
+    /// Layer a likely-answers list on top of the prior, see `WordleSolver::load_answers`
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn answers<P: Into<PathBuf>>(mut self, path: P) -> SolverConfig {
+        self.answers = Some(path.into());
+        self
+    }
+
+    /// See `WordleSolver::set_hard_mode`
+    pub fn hard_mode(mut self, hard_mode: bool) -> SolverConfig {
+        self.hard_mode = hard_mode;
+        self
+    }
+
+    /// See `WordleSolver::set_guess_threshold`
+    pub fn guess_threshold(mut self, bits: f64) -> SolverConfig {
+        self.guess_threshold = bits;
+        self
+    }
+
+    /// See `WordleSolver::set_commit_threshold`
+    pub fn commit_threshold(mut self, probability: f64) -> SolverConfig {
+        self.commit_threshold = probability;
+        self
+    }
+
+    /// See `WordleSolver::set_entropy_unit`
+    pub fn entropy_unit(mut self, unit: EntropyUnit) -> SolverConfig {
+        self.entropy_unit = unit;
+        self
+    }
+
+    pub fn max_guesses(mut self, max_guesses: usize) -> SolverConfig {
+        self.max_guesses = max_guesses;
+        self
+    }
+
+    /// See `WordleSolver::set_start_word`; checked by `build()`
+    pub fn start_word(mut self, word: &str) -> SolverConfig {
+        self.start_word = Some(word.to_string());
+        self
+    }
+
+    /// See `WordleSolver::set_exact`
+    pub fn exact(mut self, exact: bool) -> SolverConfig {
+        self.exact = exact;
+        self
+    }
+
+    /// See `WordleSolver::set_lookahead`
+    pub fn lookahead(mut self, depth: usize) -> SolverConfig {
+        self.lookahead = depth;
+        self
+    }
+
+    /// Cache word encodings and the opener in `dir`, for file dictionaries
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn cache_dir<P: Into<PathBuf>>(mut self, dir: P) -> SolverConfig {
+        self.cache_dir = Some(dir.into());
+        self
+    }
+
+    /// Load the dictionary and prior and apply the settings. Fails with
+    /// `SolverError::Config` if a file can't be read, a word doesn't fit the alphabet or
+    /// there are no words at all.
+    pub fn build(self) -> Result<WordleSolver, SolverError> {
+        let mut solver = match self.dictionary {
+            #[cfg(not(target_arch = "wasm32"))]
+            DictionarySource::File(path) => WordleSolver::load_dictionary(
+                &path,
+                &self.alphabet,
+                self.policy,
+                self.expected_moves_curve,
+                self.cache_dir.as_deref(),
+            ),
+            DictionarySource::Words(words) => {
+                WordleSolver::compute_word_encodings(&words, &self.alphabet).map(|encodings| {
+                    WordleSolver::from_encodings(
+                        encodings,
+                        &self.alphabet,
+                        self.policy,
+                        self.expected_moves_curve,
+                    )
+                })
+            }
+        }
+        .map_err(SolverError::Config)?;
+        if solver.dictionary.is_empty() {
+            return Err(SolverError::Config(
+                "the dictionary has no words".to_string(),
+            ));
+        }
+
+        verbose!(
+            "Policy {:?}, {} training buckets, budget {} guesses",
+            self.policy,
+            solver.expected_moves_curve.len(),
+            self.max_guesses
+        );
+        match &self.prior {
+            PriorConfig::Rank => verbose!(
+                "Prior: sigmoid over dictionary rank, midpoint {}, steepness {}",
+                RANK_PRIOR_MIDPOINT,
+                RANK_PRIOR_STEEPNESS
+            ),
+            #[cfg(not(target_arch = "wasm32"))]
+            prior => verbose!("Prior: {:?}", prior),
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let (path, loaded, what) = match &self.prior {
+                PriorConfig::Rank => (None, Ok(0), ""),
+                PriorConfig::Frequencies(path) => {
+                    (Some(path), solver.load_frequencies(path), "frequencies")
+                }
+                PriorConfig::Zipf(path) => (Some(path), solver.load_zipf(path), "Zipf frequencies"),
+                PriorConfig::Weights(path, default_weight) => (
+                    Some(path),
+                    solver.load_weights(path, *default_weight),
+                    "weights",
+                ),
+            };
+            match (path, loaded) {
+                (None, _) => {}
+                (Some(_), Ok(found)) => info!(
+                    "Loaded word {} for {} of {} words",
+                    what,
+                    found,
+                    solver.dictionary.len()
+                ),
+                (Some(path), Err(e)) => {
+                    return Err(SolverError::Config(format!(
+                        "failed to load {} file {}: {e}",
+                        what,
+                        path.display()
+                    )));
+                }
+            }
+
+            if let Some(path) = &self.answers {
+                let found = solver.load_answers(path).map_err(|e| {
+                    SolverError::Config(format!(
+                        "failed to load answers file {}: {e}",
+                        path.display()
+                    ))
+                })?;
+                info!(
+                    "Loaded answers list for {} of {} words",
+                    found,
+                    solver.dictionary.len()
+                );
+            }
+        }
+
+        solver.set_max_guesses(self.max_guesses);
+        solver.set_exact(self.exact);
+        solver.set_lookahead(self.lookahead);
+        solver.set_hard_mode(self.hard_mode);
+        solver.set_guess_threshold(self.guess_threshold);
+        solver.set_commit_threshold(self.commit_threshold);
+        solver.set_entropy_unit(self.entropy_unit);
+        if let Some(word) = &self.start_word {
+            solver.set_start_word(word).map_err(SolverError::Config)?;
+        }
+        Ok(solver)
+    }
+}
+
+#[derive(Clone)]
+pub struct WordleSolver {
+    pub dictionary: Arc<Vec<WordEncoding>>, // Dictionary as tuple of WordEncoding, sorted by rank. E.G. dictionary[0] is the word with the highest frequency
+    letter_scores: Arc<Vec<u32>>, // Positional letter frequency of each dictionary word, to break ties
+    alphabet: Alphabet,           // Letters the dictionary is spelled with
+    policy: Policy,               // The policy of the algorithm
+    expected_moves_curve: Vec<Bucket>, // The expected moves given an entropy (from our training)
+    previous_guesses: Vec<WordEncoding>, // Track previous guesses
+    information: Vec<(f64, f64)>, // (expected, actual) bits gained by each previous guess
+    max_guesses: usize,           // Guess budget of a game, used by MinimizeScore
+    start_word: Option<WordEncoding>, // Forced first guess, instead of the computed opener
+    base_weights: Vec<f64>, // Per-word weight the prior is proportional to, indexed like dictionary
+    #[cfg(not(target_arch = "wasm32"))]
+    cache_dir: Option<PathBuf>, // Where to persist the opener cache, if anywhere
+    opener_cache: Option<(u64, usize)>, // (opener key, dictionary index) of the best first guess
+    exact: bool,            // Evaluate every guess on every step, disabling entropy pruning
+    lookahead: usize,       // Search depth in the endgame: 1 = greedy, 2 = two-ply
+    hard_mode: bool,        // Only guess words that could still be the answer
+    guess_threshold: f64, // Under MinimizeScore, only guess possibilities once fewer bits than this remain
+    commit_threshold: f64, // Under MinimizeScore, guess a possibility outright once its prior exceeds this
+    entropy_unit: EntropyUnit, // Unit entropies are reported in; computations stay in bits
+    entropy_bounds: Vec<f64>, // Entropy of each dictionary word at its last evaluation (∞ = not yet evaluated)
+
+    // These are our state variables - should be updated on every iteration or guess
+    prior: Vec<f64>, // P_W(w): The probability mass function of how plausible our word is the answer
+    pub current_possibilities: Vec<usize>, // Set of current possibilities (W), stored as indices of elements in dictionary.
+
+    // These are values derived from our state
+    pub current_guess: Option<WordEncoding>,
+    pub current_guess_entropy: f64,
+    current_guess_match_result: Option<Vec<(Pattern, f64)>>,
+    current_guess_match_pattern_pd: Option<[f64; 243]>,
+    pub current_expected_score: f64,
+    pub current_guess_worst_case: usize, // Most possibilities any feedback to the current guess leaves
+    pub current_runner_up: Option<(WordEncoding, f64)>, // Second best guess found by step() and its entropy
+}
+
+impl WordleSolver {
+    /// Load the dictionary and build a solver; shorthand for the equivalent `SolverConfig`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn intialise(
+        dictionary_path: &Path,
+        alphabet: &Alphabet,
+        policy: Policy,
+        expected_moves_curve: Vec<Bucket>,
+        cache_dir: Option<&Path>,
+    ) -> Result<WordleSolver, String> {
+        let mut config = SolverConfig::from_file(dictionary_path)
+            .alphabet(alphabet.clone())
+            .policy(policy)
+            .expected_moves_curve(expected_moves_curve);
+        if let Some(dir) = cache_dir {
+            config = config.cache_dir(dir);
+        }
+        config.build().map_err(|e| e.to_string())
+    }
+
+    // Load the dictionary file. When `cache_dir` is set, word encodings are loaded from (or
+    // saved to) a cache file keyed by a hash of the dictionary contents. Fails if a word uses
+    // a letter outside `alphabet`.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load_dictionary(
+        dictionary_path: &Path,
+        alphabet: &Alphabet,
+        policy: Policy,
+        expected_moves_curve: Vec<Bucket>,
+        cache_dir: Option<&Path>,
+    ) -> Result<WordleSolver, String> {
+        let open =
+            || File::open(dictionary_path).map_err(|e| format!("Error opening dictionary: {}", e));
+        let dictionary_hash = content_hash(open()?, alphabet)
+            .map_err(|e| format!("Error reading dictionary: {}", e))?;
+
+        let dictionary = match cache_dir.and_then(|dir| load_encoding_cache(dir, dictionary_hash)) {
+            Some(encodings) => encodings,
+            None => {
+                let (encodings, skipped, first_skipped) =
+                    stream_word_encodings(io::BufReader::new(open()?), alphabet)
+                        .map_err(|e| format!("Error reading dictionary: {}", e))?;
+                if let Some(first) = first_skipped {
+                    note!(
+                        "⚠️  Skipped {} invalid line(s) in {} (first, {})",
+                        skipped,
+                        dictionary_path.display(),
+                        first
+                    );
+                }
+                if let Some(dir) = cache_dir
+                    && let Err(e) = save_encoding_cache(dir, dictionary_hash, &encodings)
+                {
+                    note!("⚠️  Could not write encodings cache: {e}");
+                }
+                encodings
+            }
+        };
+        info!("Loaded dictionary with {} words", dictionary.len());
+
+        let mut solver =
+            WordleSolver::from_encodings(dictionary, alphabet, policy, expected_moves_curve);
+        solver.cache_dir = cache_dir.map(Path::to_path_buf);
+
+        Ok(solver)
+    }
+
+    /// Build a solver from an in-memory word list, without touching the filesystem;
+    /// shorthand for the equivalent `SolverConfig`.
+    pub fn from_words(
+        words: &[String],
+        alphabet: &Alphabet,
+        policy: Policy,
+        expected_moves_curve: Vec<Bucket>,
+    ) -> Result<WordleSolver, String> {
+        SolverConfig::from_words(words.to_vec())
+            .alphabet(alphabet.clone())
+            .policy(policy)
+            .expected_moves_curve(expected_moves_curve)
+            .build()
+            .map_err(|e| e.to_string())
+    }
+
+    fn from_encodings(
+        dictionary: Vec<WordEncoding>,
+        alphabet: &Alphabet,
+        policy: Policy,
+        expected_moves_curve: Vec<Bucket>,
+    ) -> WordleSolver {
+        let dictionary_len = dictionary.len();
+
+        let mut solver = WordleSolver {
+            letter_scores: Arc::new(positional_letter_scores(&dictionary)),
+            dictionary: Arc::new(dictionary),
+            alphabet: alphabet.clone(),
+            policy,
+            previous_guesses: Vec::new(),
+            information: Vec::new(),
+            max_guesses: DEFAULT_MAX_GUESSES,
+            start_word: None,
+            base_weights: rank_weights(dictionary_len),
+            #[cfg(not(target_arch = "wasm32"))]
+            cache_dir: None,
+            opener_cache: None,
+            exact: false,
+            lookahead: 1,
+            hard_mode: false,
+            guess_threshold: 0.0,
+            commit_threshold: COMMIT_THRESHOLD,
+            entropy_unit: EntropyUnit::Bits,
+            entropy_bounds: vec![f64::INFINITY; dictionary_len],
+            prior: vec![0.0; dictionary_len],
+            current_possibilities: (0..dictionary_len).collect(),
+            current_guess: None,
+            current_guess_entropy: 0.0,
+            current_guess_match_result: None,
+            current_guess_match_pattern_pd: None,
+            current_expected_score: f64::INFINITY,
+            current_guess_worst_case: usize::MAX,
+            current_runner_up: None,
+            expected_moves_curve,
+        };
+
+        //  Update the prior in the solver before returning it
+        solver.update_prior();
+
+        solver
+    }
+
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    /// Switch to `policy`, scoring with `expected_moves_curve` (empty for the untrained
+    /// estimate). The dictionary and its encodings are kept, so comparing policies doesn't
+    /// load them again; the cached opener is keyed by policy and stays valid.
+    pub fn set_policy(&mut self, policy: Policy, expected_moves_curve: Vec<Bucket>) {
+        self.policy = policy;
+        self.expected_moves_curve = expected_moves_curve;
+    }
+
+    /// Set the number of guesses a game allows (`DEFAULT_MAX_GUESSES` by default).
+    pub fn set_max_guesses(&mut self, max_guesses: usize) {
+        self.max_guesses = max_guesses;
+    }
+
+    /// Guesses left in the budget, including the one about to be made
+    pub fn guesses_left(&self) -> usize {
+        self.max_guesses.saturating_sub(self.previous_guesses.len())
+    }
+
+    /// Always open with `word` instead of computing the first guess. The word must be in the
+    /// dictionary.
+    pub fn set_start_word(&mut self, word: &str) -> Result<(), String> {
+        let encoding = WordEncoding::from_string(word, &self.alphabet)
+            .map_err(|e| format!("invalid start word: {e}"))?;
+        if !self.dictionary.contains(&encoding) {
+            return Err(format!("start word '{}' is not in the dictionary", word));
+        }
+        self.start_word = Some(encoding);
+        Ok(())
+    }
+
+    /// Evaluate every guess on every step instead of pruning by entropy bound (see `step()`).
+    pub fn set_exact(&mut self, exact: bool) {
+        self.exact = exact;
+    }
+
+    /// Search depth used once few possibilities remain: 1 (the default) picks greedily by
+    /// the policy, 2 adds a two-ply search (see `step()`).
+    pub fn set_lookahead(&mut self, depth: usize) {
+        self.lookahead = depth;
+    }
+
+    /// Only guess words that could still be the answer. Every such word reuses all the
+    /// greens and yellows revealed so far, so this obeys Wordle's hard-mode rule (if a
+    /// little more strictly than it requires).
+    pub fn set_hard_mode(&mut self, hard_mode: bool) {
+        self.hard_mode = hard_mode;
+    }
+
+    /// Under `MinimizeScore`, only guess words that could still be the answer once the
+    /// remaining entropy, `log2(|possibilities|)`, drops below `bits`. 0 (the default) never
+    /// does. See `step()` for how this combines with the guess budget.
+    pub fn set_guess_threshold(&mut self, bits: f64) {
+        self.guess_threshold = bits;
+    }
+
+    /// Under `MinimizeScore`, guess a remaining possibility without searching once its prior
+    /// exceeds `probability` (`COMMIT_THRESHOLD` by default). 1 never does. See `step()`.
+    pub fn set_commit_threshold(&mut self, probability: f64) {
+        self.commit_threshold = probability;
+    }
+
+    /// Report entropies in `unit` (bits by default). The solver's own fields, thresholds
+    /// and training curve stay in bits; `in_entropy_unit` converts for display.
+    pub fn set_entropy_unit(&mut self, unit: EntropyUnit) {
+        self.entropy_unit = unit;
+    }
+
+    pub fn entropy_unit(&self) -> EntropyUnit {
+        self.entropy_unit
+    }
+
+    /// `bits` of entropy in the reporting unit, e.g. for `current_guess_entropy`
+    pub fn in_entropy_unit(&self, bits: f64) -> f64 {
+        self.entropy_unit.from_bits(bits)
+    }
+
+    pub fn reset(&mut self) {
+        self.clear_guess();
+        self.previous_guesses.clear();
+        self.information.clear();
+
+        // Reset possibilties
+        self.current_possibilities = (0..self.dictionary.len()).collect();
+
+        // Reset prior
+        self.update_prior();
+
+        // Entropies from another game say nothing about this one
+        self.entropy_bounds.fill(f64::INFINITY);
+    }
+
+    // Update our prior with the current possibilities
+    pub fn update_prior(&mut self) {
+        let mut weights = vec![0.0; self.dictionary.len()];
+
+        let mut sum_weight: f64 = 0.0;
+        for w in self.current_possibilities.iter() {
+            weights[*w] = self.base_weights[*w];
+            sum_weight += weights[*w]
+        }
+
+        // Update the prior probabilities. An all-zero weight can't be normalised; it only
+        // happens with no possibilities left, where every prior is zero anyway.
+        for (p, w) in self.prior.iter_mut().zip(weights.iter()) {
+            *p = if sum_weight > 0.0 {
+                w / sum_weight
+            } else {
+                0.0
+            };
+        }
+    }
+
+    /// Weight the prior by a unigram frequency file of `word,count` lines instead of by
+    /// dictionary position. Each word gets its log-frequency normalised to `[0, 1]`; words
+    /// missing from the file get `FREQUENCY_FLOOR_WEIGHT` so they stay possible answers.
+    /// Returns how many dictionary words were found in the file.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_frequencies<P: AsRef<Path>>(&mut self, path: P) -> io::Result<usize> {
+        let counts = read_word_values(path.as_ref())?;
+
+        let log_counts: Vec<Option<f64>> = self
+            .dictionary
+            .iter()
+            .map(|word| counts.get(&word.to_string()).map(|c| c.ln_1p()))
+            .collect();
+        let max_log_count = log_counts.iter().flatten().fold(0.0, |a: f64, &b| a.max(b));
+        let found = log_counts.iter().flatten().count();
+
+        self.base_weights = log_counts
+            .iter()
+            .map(|c| match c {
+                Some(c) if max_log_count > 0.0 => (c / max_log_count).max(FREQUENCY_FLOOR_WEIGHT),
+                _ => FREQUENCY_FLOOR_WEIGHT,
+            })
+            .collect();
+        self.update_prior();
+
+        Ok(found)
+    }
+
+    /// Weight the prior by real word frequencies from a `word,zipf` file, on the Zipf scale
+    /// of e.g. wordfreq (log10 of occurrences per billion words). Unlike `load_frequencies`,
+    /// which compresses counts logarithmically, each word's weight is proportional to its
+    /// frequency, `10^(zipf - max zipf)`, so `MaximizeEntropy` expects answers as often as
+    /// they occur in text. Weights are floored at `FREQUENCY_FLOOR_WEIGHT`, which missing
+    /// words also get. Returns how many dictionary words were found in the file.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_zipf<P: AsRef<Path>>(&mut self, path: P) -> io::Result<usize> {
+        let zipfs = read_word_values(path.as_ref())?;
+
+        let word_zipfs: Vec<Option<f64>> = self
+            .dictionary
+            .iter()
+            .map(|word| zipfs.get(&word.to_string()).copied())
+            .collect();
+        let max_zipf = word_zipfs
+            .iter()
+            .flatten()
+            .fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+        let found = word_zipfs.iter().flatten().count();
+
+        self.base_weights = word_zipfs
+            .iter()
+            .map(|z| match z {
+                Some(z) => 10f64.powf(z - max_zipf).max(FREQUENCY_FLOOR_WEIGHT),
+                None => FREQUENCY_FLOOR_WEIGHT,
+            })
+            .collect();
+        self.update_prior();
+
+        Ok(found)
+    }
+
+    /// Mark the words of `path` (one per line, like a dictionary file) as the likely answers,
+    /// e.g. NYT's curated answers among the allowed guesses. On top of whichever prior is
+    /// loaded, every other word's weight is multiplied by `NON_ANSWER_WEIGHT`, so it stays
+    /// possible but is almost never expected. Call it after the other `load_*` methods, which
+    /// replace the weights. Returns how many dictionary words are in the list.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_answers<P: AsRef<Path>>(&mut self, path: P) -> io::Result<usize> {
+        // Spelled through the solver's alphabet, as the dictionary is; e.g. `to_uppercase`
+        // would turn ß into SS. Words the alphabet can't spell can't be in the dictionary.
+        let answers: HashSet<[char; 5]> = parse_dictionary(&std::fs::read(path)?)
+            .iter()
+            .filter_map(|word| WordEncoding::from_string(word, &self.alphabet).ok())
+            .map(|encoding| encoding.positions)
+            .collect();
+
+        let mut found = 0;
+        for (word, weight) in self.dictionary.iter().zip(self.base_weights.iter_mut()) {
+            match answers.contains(&word.positions) {
+                true => found += 1,
+                false => *weight *= NON_ANSWER_WEIGHT,
+            }
+        }
+        self.update_prior();
+
+        Ok(found)
+    }
+
+    /// Use the weights of a `word,weight` file as the base weights of the prior, as is;
+    /// words missing from the file get `default_weight`. Returns how many dictionary words
+    /// were found in the file.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_weights<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        default_weight: f64,
+    ) -> io::Result<usize> {
+        let weights = read_word_values(path.as_ref())?;
+
+        let mut found = 0;
+        self.base_weights = self
+            .dictionary
+            .iter()
+            .map(|word| match weights.get(&word.to_string()) {
+                Some(weight) => {
+                    found += 1;
+                    *weight
+                }
+                None => default_weight,
+            })
+            .collect();
+        self.update_prior();
+
+        Ok(found)
+    }
+
+    /// Play the current guess and keep the possibilities consistent with the feedback
+    /// returned by `callback`. Feedback that no possibility is consistent with is rejected
+    /// with `NoCandidates` and leaves the solver untouched, so it can be corrected.
+    pub fn guess<CheckFunction>(&mut self, callback: CheckFunction) -> Result<(), SolverError>
+    where
+        CheckFunction: Fn(&WordEncoding) -> MatchResult,
+    {
+        match &self.current_guess {
+            Some(some_guess) => {
+                let actual_match = callback(some_guess);
+                self.guess_any_of(&[actual_match])
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// `guess()` with the feedback `oracle` gives for the current guess
+    pub fn guess_with(&mut self, oracle: &impl Oracle) -> Result<(), SolverError> {
+        self.guess(|guess| oracle.check(guess))
+    }
+
+    /// Start a new game and play it against `secret` until it is solved or the guess budget
+    /// (see `set_max_guesses`) runs out, returning every guess with its feedback
+    pub fn play_out(&mut self, secret: &WordEncoding) -> GameTranscript {
+        self.play_out_with(secret, WordleSolver::step)
+    }
+
+    /// `play_out()`, choosing each guess with `step` instead of `step()` itself, e.g. to
+    /// time it
+    pub fn play_out_with(
+        &mut self,
+        secret: &WordEncoding,
+        mut step: impl FnMut(&mut WordleSolver) -> Result<WordEncoding, SolverError>,
+    ) -> GameTranscript {
+        self.reset();
+        let oracle = FixedSecret(secret.clone());
+        let mut transcript = GameTranscript {
+            turns: Vec::new(),
+            solved: false,
+            error: None,
+        };
+
+        while transcript.guesses() < self.max_guesses {
+            let remaining = self.current_possibilities.len();
+            let guess = match step(self) {
+                Ok(guess) => guess,
+                Err(e) => {
+                    transcript.error = Some(e);
+                    break;
+                }
+            };
+            let pattern = oracle.check(&guess);
+            transcript.turns.push(TranscriptTurn {
+                guess,
+                pattern,
+                remaining,
+                entropy: self.current_guess_entropy,
+            });
+
+            if is_solved(&pattern) {
+                transcript.solved = true;
+                break;
+            }
+            if transcript.guesses() < self.max_guesses
+                && let Err(e) = self.guess_with(&oracle)
+            {
+                transcript.error = Some(e);
+                break;
+            }
+        }
+        transcript
+    }
+
+    /// Like `guess()`, for feedback that is only known to be one of `patterns` (see
+    /// `parse_uncertain_feedback`): keeps the possibilities consistent with any of them.
+    pub fn guess_any_of(&mut self, patterns: &[MatchResult]) -> Result<(), SolverError> {
+        let patterns: Vec<Pattern> = patterns.iter().map(pack_pattern).collect();
+        if let Some(some_guess) = &self.current_guess {
+            let keep: Vec<usize> = self
+                .current_guess_match_result
+                .as_ref()
+                .unwrap()
+                .iter()
+                .zip(self.current_possibilities.iter())
+                .filter(|(val, _)| patterns.contains(&val.0))
+                .map(|(_, index)| *index)
+                .collect();
+            if keep.is_empty() {
+                return Err(SolverError::NoCandidates);
+            }
+
+            self.previous_guesses.push(some_guess.clone());
+            self.record_information(self.current_guess_entropy, keep.len());
+            self.current_possibilities = keep;
+            self.update_prior();
+        }
+        Ok(())
+    }
+
+    // Note what the guess being played was expected to reveal, and what narrowing the
+    // possibilities down to `kept` words actually revealed, in bits
+    fn record_information(&mut self, expected: f64, kept: usize) {
+        let actual = (self.current_possibilities.len() as f64).log2() - (kept as f64).log2();
+        self.information.push((expected, actual));
+    }
+
+    /// The (expected, actual) information in bits gained by each guess of the game so far.
+    /// Expected is the guess' entropy over the possibilities it was played against; actual
+    /// is how far it cut them down, `log2(before / after)`. A guess gaining more than it was
+    /// expected to was lucky.
+    pub fn information_gained(&self) -> &[(f64, f64)] {
+        &self.information
+    }
+
+    /// Play `guess` and keep the possibilities that give `pattern` against it, whatever the
+    /// current guess is. The current guess is discarded since its match results no longer
+    /// line up. Like `guess()`, a pattern no possibility gives is rejected with `NoCandidates`
+    /// and leaves the solver untouched.
+    pub fn apply(&mut self, guess: &WordEncoding, pattern: MatchResult) -> Result<(), SolverError> {
+        let dictionary = Arc::clone(&self.dictionary);
+        let pattern = pack_pattern(&pattern);
+        let keep: Vec<usize> = self
+            .current_possibilities
+            .iter()
+            .copied()
+            .filter(|i| guess.match_pattern(&dictionary[*i]) == pattern)
+            .collect();
+        if keep.is_empty() {
+            return Err(SolverError::NoCandidates);
+        }
+
+        let index = dictionary.iter().position(|word| word == guess);
+        let (_, _, expected, _) = self.evaluate_guess(index, guess);
+        self.previous_guesses.push(guess.clone());
+        self.record_information(expected, keep.len());
+        self.current_possibilities = keep;
+        self.clear_guess();
+
+        self.update_prior();
+        Ok(())
+    }
+
+    /// Keep only the possibilities `other` also has left, pooling the feedback two players
+    /// got for their own guesses. `other`'s guesses count as played here too, so neither is
+    /// suggested again. Solvers with different dictionaries (by `dictionary_fingerprint`)
+    /// are rejected with `DictionaryMismatch`, and an empty intersection with `NoCandidates`;
+    /// either leaves the solver untouched.
+    pub fn intersect(&mut self, other: &WordleSolver) -> Result<(), SolverError> {
+        if self.dictionary_fingerprint() != other.dictionary_fingerprint() {
+            return Err(SolverError::DictionaryMismatch);
+        }
+        let mut in_other = vec![false; self.dictionary.len()];
+        for &i in &other.current_possibilities {
+            in_other[i] = true;
+        }
+        let keep: Vec<usize> = self
+            .current_possibilities
+            .iter()
+            .copied()
+            .filter(|&i| in_other[i])
+            .collect();
+        if keep.is_empty() {
+            return Err(SolverError::NoCandidates);
+        }
+
+        for guess in &other.previous_guesses {
+            if !self.previous_guesses.contains(guess) {
+                self.previous_guesses.push(guess.clone());
+            }
+        }
+        self.current_possibilities = keep;
+        self.clear_guess();
+
+        self.update_prior();
+        Ok(())
+    }
+
+    /// The feedback an adversarial host (as in Absurdle) gives `guess`: of the patterns some
+    /// remaining possibility would produce, the one keeping the most possibilities, ties
+    /// going to fewer greens, then fewer yellows. It is only all green once `guess` is the
+    /// single word left.
+    pub fn adversarial_feedback(&self, guess: &WordEncoding) -> MatchResult {
+        let kinds = |pattern: Pattern, kind: MatchKind| {
+            unpack_pattern(pattern)
+                .iter()
+                .filter(|k| **k == kind)
+                .count()
+        };
+        let (pattern, _) = group_by_pattern(guess, &self.current_possibilities, &self.dictionary)
+            .into_iter()
+            .max_by(|(a, a_bucket), (b, b_bucket)| {
+                a_bucket
+                    .len()
+                    .cmp(&b_bucket.len())
+                    .then(kinds(*b, MatchKind::Match).cmp(&kinds(*a, MatchKind::Match)))
+                    .then(kinds(*b, MatchKind::Partial).cmp(&kinds(*a, MatchKind::Partial)))
+                    .then(b.cmp(a))
+            })
+            .expect("a solver always has a possibility left");
+        unpack_pattern(pattern)
+    }
+
+    /// Pick the next guess according to the active policy.
+    ///
+    /// From a fresh state the start word is played if one is set. Otherwise the answer only
+    /// depends on the dictionary, prior and policy, so the opener is cached (in memory, and on disk when the solver has a cache directory) under
+    /// a key derived from all three, and reused instead of scanning the whole dictionary.
+    ///
+    /// Guesses whose objective is within `TIE_EPSILON` of the current best are treated as
+    /// tied, and ties are broken deterministically so training and interactive runs are
+    /// reproducible regardless of dictionary order:
+    /// 1. prefer a guess that is itself in `current_possibilities` (it can win immediately),
+    /// 2. then the guess with the higher positional letter frequency: summed over positions,
+    ///    how many dictionary words have its letter there, so e.g. SLATE beats an oddity,
+    /// 3. then the alphabetically smaller word.
+    ///
+    /// With a single guess left in the budget, only words in `current_possibilities` are
+    /// considered, since any other guess is a certain loss. The same goes once at most
+    /// `ENDGAME_THRESHOLD` possibilities remain and one of them tells all the others apart:
+    /// guessing it can't be beaten, so information-only guesses aren't worth their cost.
+    /// In hard mode (see `set_hard_mode`) only those words are ever considered.
+    ///
+    /// Under `MinimizeScore` with a guess threshold (see `set_guess_threshold`), the same
+    /// restriction applies once `log2(|possibilities|)` is below it. The expected score
+    /// already penalises guesses likely to run past the budget, but only through the
+    /// training curve; the threshold is an explicit rule on top, applied however many
+    /// guesses are left. A threshold of 2 bits, say, always goes for the answer once three
+    /// or fewer words remain.
+    ///
+    /// Under `MinimizeScore`, once one possibility's prior exceeds the commit threshold (see
+    /// `set_commit_threshold`) it is guessed straight away, skipping the search. Its expected
+    /// score is still computed as usual, so the budget penalty stays reflected in
+    /// `current_expected_score`; and since it wins with that probability, no other guess could
+    /// have scored meaningfully better. A start word still takes precedence on the first guess.
+    ///
+    /// Unless the solver is `exact`, `MaximizeEntropy` also prunes, as a heuristic: guesses
+    /// are visited in decreasing order of their entropy at an earlier step of the same game,
+    /// and once the best found so far is more than `ENTROPY_PRUNE_MARGIN` above a guess'
+    /// earlier entropy, it and the rest are skipped. That earlier entropy is no bound (over
+    /// fewer, renormalised possibilities a guess' entropy can rise), so a pruned search may
+    /// miss the best guess; `exact` never does. Each game starts with nothing to prune by.
+    /// `MinimizeScore` and `Blend` never prune because a low entropy guess can still win on
+    /// its prior.
+    ///
+    /// With a `lookahead` of 2 and at most `LOOKAHEAD_THRESHOLD` possibilities left, the
+    /// `LOOKAHEAD_CANDIDATES` best guesses by entropy and every remaining possibility are
+    /// re-ranked by expected total guesses: each pattern bucket a guess leads to is followed
+    /// up by its own best one-ply guess. `current_expected_score` then holds that estimate.
+    ///
+    /// The choice itself is `best_guess()`; `step()` also stores it in the `current_*`
+    /// fields and updates the opener cache and entropy bounds. Returns the chosen guess, or
+    /// why there is none.
+    pub fn step(&mut self) -> Result<WordEncoding, SolverError> {
+        if self.current_possibilities.is_empty() {
+            self.current_guess = None;
+            return Err(SolverError::NoCandidates);
+        }
+
+        let (info, evaluated) = self.select_guess();
+        for (i, entropy) in &evaluated {
+            self.entropy_bounds[*i] = *entropy;
+        }
+        if self.is_fresh()
+            && self.start_word.is_none()
+            && let Some(info) = &info
+        {
+            self.remember_opener(&info.word, !evaluated.is_empty());
+        }
+
+        match info {
+            Some(info) => {
+                let guess = info.word.clone();
+                self.store_guess(info);
+                Ok(guess)
+            }
+            None => {
+                self.clear_guess();
+                Err(SolverError::Exhausted)
+            }
+        }
+    }
+
+    /// The guess `step()` would choose now, without storing it or touching any other state,
+    /// e.g. to preview a suggestion. `None` once no possibility, or no unplayed guess, is left.
+    pub fn best_guess(&self) -> Option<GuessInfo> {
+        if self.current_possibilities.is_empty() {
+            return None;
+        }
+        self.select_guess().0
+    }
+
+    // Whether no guess has narrowed the dictionary yet, so the opener applies
+    fn is_fresh(&self) -> bool {
+        self.previous_guesses.is_empty()
+            && self.current_possibilities.len() == self.dictionary.len()
+    }
+
+    // The guess from the start word, the opener cache or a full search, and the entropy of
+    // every dictionary word the search evaluated (as (index, entropy))
+    fn select_guess(&self) -> (Option<GuessInfo>, Vec<(usize, f64)>) {
+        let fresh = self.is_fresh();
+        if fresh && let Some(start) = &self.start_word {
+            return (Some(self.rate_guess(start.clone())), Vec::new());
+        }
+        if let Some(index) = self.committed_answer() {
+            return (
+                Some(self.rate_guess(self.dictionary[index].clone())),
+                Vec::new(),
+            );
+        }
+        if fresh && let Some(index) = self.cached_opener(self.opener_key()) {
+            return (
+                Some(self.rate_guess(self.dictionary[index].clone())),
+                Vec::new(),
+            );
+        }
+        let (info, evaluated) = self.search_guess();
+        (info.map(|info| self.look_ahead(info)), evaluated)
+    }
+
+    // Under `MinimizeScore`, the possibility whose prior exceeds the commit threshold, if any
+    fn committed_answer(&self) -> Option<usize> {
+        if self.policy != Policy::MinimizeScore {
+            return None;
+        }
+        self.current_possibilities
+            .iter()
+            .copied()
+            .find(|&i| self.prior[i] > self.commit_threshold)
+            .filter(|&i| !self.previous_guesses.contains(&self.dictionary[i]))
+    }
+
+    // Cache `guess` as the opener, writing it to disk too when it was just `searched` for
+    // rather than read from the cache
+    #[cfg_attr(target_arch = "wasm32", allow(unused_variables))] // No disk cache in the browser
+    fn remember_opener(&mut self, guess: &WordEncoding, searched: bool) {
+        let key = self.opener_key();
+        let Some(index) = self.dictionary.iter().position(|word| word == guess) else {
+            return;
+        };
+        self.opener_cache = Some((key, index));
+        #[cfg(not(target_arch = "wasm32"))]
+        if searched
+            && let Some(dir) = &self.cache_dir
+            && let Err(e) = save_opener_cache(dir, key, index)
+        {
+            note!("⚠️  Could not write opener cache: {e}");
+        }
+    }
+
+    // Key identifying everything the opener depends on: dictionary, prior, policy, curve,
+    // lookahead and, under `MinimizeScore`, the guess threshold (on a small enough
+    // dictionary, both already apply to the first guess)
+    fn opener_key(&self) -> u64 {
+        let mut hasher = fingerprint_hasher(&self.dictionary);
+        for p in &self.prior {
+            p.to_bits().hash(&mut hasher);
+        }
+        let (policy, lambda) = match self.policy {
+            Policy::MaximizeEntropy => (0u8, 0.0),
+            Policy::MinimizeScore => (1, 0.0),
+            Policy::MinimizeWorstCase => (2, 0.0),
+            Policy::Blend { lambda } => (3, lambda),
+        };
+        policy.hash(&mut hasher);
+        if let Policy::Blend { .. } = self.policy {
+            lambda.to_bits().hash(&mut hasher);
+        }
+        if self.policy == Policy::MinimizeScore {
+            self.guess_threshold.to_bits().hash(&mut hasher);
+        }
+        self.max_guesses.hash(&mut hasher);
+        self.lookahead.hash(&mut hasher);
+        for bucket in &self.expected_moves_curve {
+            bucket.centre.to_bits().hash(&mut hasher);
+            bucket.avg_moves.to_bits().hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    // The dictionary's fingerprint extended with the alphabet it is spelled with
+    fn dictionary_hash(&self) -> u64 {
+        let mut hasher = fingerprint_hasher(&self.dictionary);
+        hasher.write_letters(&self.alphabet.letters);
+        hasher.finish()
+    }
+
+    /// `dictionary_fingerprint` of the solver's dictionary
+    pub fn dictionary_fingerprint(&self) -> u64 {
+        fingerprint_hasher(&self.dictionary).finish()
+    }
+
+    // Dictionary index of the cached opener for `key`, from memory or disk
+    fn cached_opener(&self, key: u64) -> Option<usize> {
+        if let Some((cached_key, index)) = self.opener_cache
+            && cached_key == key
+        {
+            return Some(index);
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(dir) = &self.cache_dir
+            && let Some(index) = load_opener_cache(dir, key)
+            && index < self.dictionary.len()
+        {
+            return Some(index);
+        }
+
+        None
+    }
+
+    // Forget the current guess and everything derived from it
+    fn clear_guess(&mut self) {
+        self.current_guess = None;
+        self.current_guess_entropy = 0.0;
+        self.current_guess_match_result = None;
+        self.current_guess_match_pattern_pd = None;
+        self.current_expected_score = f64::INFINITY;
+        self.current_guess_worst_case = usize::MAX;
+        self.current_runner_up = None;
+    }
+
+    // Scan the dictionary for the best guess against the current possibilities, returning it
+    // with the entropy of every guess evaluated on the way
+    fn search_guess(&self) -> (Option<GuessInfo>, Vec<(usize, f64)>) {
+        let mut is_candidate = vec![false; self.dictionary.len()];
+        for j in self.current_possibilities.iter() {
+            is_candidate[*j] = true;
+        }
+        let mut best: Option<(usize, Rating)> = None;
+        let mut runner_up: Option<(usize, Rating)> = None;
+        let mut chosen: Option<GuessInfo> = None;
+        let mut evaluated = Vec::new();
+        let candidates_only = self.candidates_only();
+        let prune = !self.exact && self.policy == Policy::MaximizeEntropy;
+
+        // When pruning, visit the most promising guesses first so the best is found early and
+        // the remaining (less promising) guesses can be skipped together
+        let mut order: Vec<usize> = (0..self.dictionary.len()).collect();
+        if prune {
+            order.sort_by(|&a, &b| self.entropy_bounds[b].total_cmp(&self.entropy_bounds[a]));
+        }
+
+        // Calculate entropy of every possibilities
+        for i in order {
+            let guess = &self.dictionary[i];
+            // Do not repeat our guess
+            if self.previous_guesses.contains(guess) || (candidates_only && !is_candidate[i]) {
+                continue;
+            }
+            if prune
+                && let Some(chosen) = &chosen
+                && self.entropy_bounds[i] + ENTROPY_PRUNE_MARGIN < chosen.entropy
+            {
+                break;
+            }
+
+            let (match_results, match_pattern_pd, entropy, expected_score) =
+                self.evaluate_guess(Some(i), guess);
+            evaluated.push((i, entropy));
+            let worst_case = largest_group(&match_results);
+
+            let contender = (
+                i,
+                Rating {
+                    entropy,
+                    expected_score,
+                    worst_case,
+                },
+            );
+            match best {
+                Some(incumbent) if !self.beats(contender, incumbent, &is_candidate) => {
+                    if runner_up.is_none_or(|r| self.beats(contender, r, &is_candidate)) {
+                        runner_up = Some(contender);
+                    }
+                }
+                _ => {
+                    runner_up = best;
+                    best = Some(contender);
+                    chosen = Some(GuessInfo {
+                        word: guess.clone(),
+                        entropy,
+                        expected_score,
+                        worst_case,
+                        runner_up: None,
+                        match_results,
+                        match_pattern_pd,
+                    });
+                }
+            }
+        }
+
+        let chosen = chosen.map(|info| GuessInfo {
+            runner_up: runner_up.map(|(j, rating)| (self.dictionary[j].clone(), rating.entropy)),
+            ..info
+        });
+        (chosen, evaluated)
+    }
+
+    // Whether guess `i` rates better than guess `j` under the policy, ties broken by
+    // `wins_tie`
+    fn beats(
+        &self,
+        (i, rating): (usize, Rating),
+        (j, other): (usize, Rating),
+        is_candidate: &[bool],
+    ) -> bool {
+        // How much better `i` is than `j` (positive = better)
+        let gain = match self.policy {
+            Policy::MaximizeEntropy => rating.entropy - other.entropy,
+            Policy::MinimizeScore => other.expected_score - rating.expected_score,
+            // Break worst-case ties by entropy
+            Policy::MinimizeWorstCase if rating.worst_case != other.worst_case => {
+                other.worst_case as f64 - rating.worst_case as f64
+            }
+            Policy::MinimizeWorstCase => rating.entropy - other.entropy,
+            Policy::Blend { lambda } => {
+                (rating.entropy - lambda * rating.expected_score)
+                    - (other.entropy - lambda * other.expected_score)
+            }
+        };
+        if gain > TIE_EPSILON {
+            true
+        } else if gain < -TIE_EPSILON {
+            false
+        } else {
+            self.wins_tie(i, is_candidate[i], j, is_candidate[j])
+        }
+    }
+
+    // Replace the `greedy` guess with the best two-ply guess, when lookahead is enabled
+    fn look_ahead(&self, greedy: GuessInfo) -> GuessInfo {
+        let n = self.current_possibilities.len();
+        if self.lookahead < 2 || n <= 2 || n > LOOKAHEAD_THRESHOLD {
+            return greedy;
+        }
+        let candidates_only = self.candidates_required();
+
+        // Shortlist the best guesses by entropy, plus every remaining possibility
+        let mut ranked: Vec<(usize, f64)> = self
+            .dictionary
+            .iter()
+            .enumerate()
+            .filter(|(_, word)| !self.previous_guesses.contains(word))
+            .map(|(i, word)| (i, self.evaluate_guess(Some(i), word).2))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let mut shortlist: Vec<usize> = match candidates_only {
+            true => Vec::new(),
+            false => ranked
+                .iter()
+                .take(LOOKAHEAD_CANDIDATES)
+                .map(|(i, _)| *i)
+                .collect(),
+        };
+        for i in &self.current_possibilities {
+            if !shortlist.contains(i) && !self.previous_guesses.contains(&self.dictionary[*i]) {
+                shortlist.push(*i);
+            }
+        }
+
+        let mut best: Option<(usize, f64)> = None;
+        for i in shortlist {
+            let cost = self.two_ply_cost(i);
+            let is_better = match best {
+                None => true,
+                Some((_, best_cost)) if cost < best_cost - TIE_EPSILON => true,
+                Some((_, best_cost)) if cost > best_cost + TIE_EPSILON => false,
+                Some((best_i, _)) => self.wins_tie(
+                    i,
+                    self.current_possibilities.contains(&i),
+                    best_i,
+                    self.current_possibilities.contains(&best_i),
+                ),
+            };
+            if is_better {
+                best = Some((i, cost));
+            }
+        }
+
+        match best {
+            Some((i, cost)) => GuessInfo {
+                expected_score: cost,
+                ..self.rate_guess(self.dictionary[i].clone())
+            },
+            None => greedy,
+        }
+    }
+
+    // Expected total guesses when guessing dictionary word `g` now, then the best one-ply
+    // guess for whichever bucket of possibilities its feedback leaves
+    fn two_ply_cost(&self, g: usize) -> f64 {
+        let cost: f64 = group_by_pattern(
+            &self.dictionary[g],
+            &self.current_possibilities,
+            &self.dictionary,
+        )
+        .iter()
+        .filter(|(pattern, _)| *pattern != SOLVED_PATTERN)
+        .map(|(_, bucket)| {
+            let mass: f64 = bucket.iter().map(|i| self.prior[*i]).sum();
+            mass * self.follow_up_cost(bucket, g)
+        })
+        .sum();
+        1.0 + cost
+    }
+
+    // Expected guesses to solve `bucket` by playing its best guess by entropy, then the
+    // likeliest word of whichever bucket that leaves, assuming a miss there costs one more
+    fn follow_up_cost(&self, bucket: &[usize], already_guessed: usize) -> f64 {
+        if bucket.len() == 1 {
+            return 1.0;
+        }
+
+        let mut best: Option<(usize, f64)> = None;
+        for (h, word) in self.dictionary.iter().enumerate() {
+            if h == already_guessed
+                || self.previous_guesses.contains(word)
+                || (self.hard_mode && !bucket.contains(&h))
+            {
+                continue;
+            }
+            let match_results: Vec<(Pattern, f64)> = bucket
+                .iter()
+                .map(|j| (word.match_pattern(&self.dictionary[*j]), self.prior[*j]))
+                .collect();
+            let entropy = WordleSolver::compute_entropy(WordleSolver::compute_match_pattern_pd(
+                &match_results,
+            ));
+            let is_better = match best {
+                None => true,
+                Some((_, e)) if entropy > e + TIE_EPSILON => true,
+                Some((_, e)) if entropy < e - TIE_EPSILON => false,
+                Some((best_h, _)) => {
+                    self.wins_tie(h, bucket.contains(&h), best_h, bucket.contains(&best_h))
+                }
+            };
+            if is_better {
+                best = Some((h, entropy));
+            }
+        }
+        let Some((h, _)) = best else {
+            return bucket.len() as f64; // nothing left to split with: guess the words in turn
+        };
+
+        let mass: f64 = bucket.iter().map(|i| self.prior[*i]).sum();
+        let cost: f64 = group_by_pattern(&self.dictionary[h], bucket, &self.dictionary)
+            .iter()
+            .filter(|(pattern, _)| *pattern != SOLVED_PATTERN)
+            .map(|(_, sub_bucket)| {
+                let sub_mass: f64 = sub_bucket.iter().map(|i| self.prior[*i]).sum();
+                let likeliest = sub_bucket
+                    .iter()
+                    .map(|i| self.prior[*i])
+                    .fold(0.0, f64::max);
+                // Guess the likeliest word; if that misses, assume one more guess solves it
+                let remaining = 2.0 - likeliest / sub_mass;
+                sub_mass / mass * remaining
+            })
+            .sum();
+        1.0 + cost
+    }
+
+    /// The feedback `guess` could get against the current possibilities: each pattern with
+    /// its probability under the prior and how many possibilities give it, most likely
+    /// first. Patterns no possibility gives are left out.
+    pub fn pattern_distribution(&self, guess: &WordEncoding) -> Vec<(MatchResult, f64, usize)> {
+        let index = self.dictionary.iter().position(|word| word == guess);
+        let (match_results, match_pattern_pd, _, _) = self.evaluate_guess(index, guess);
+
+        let mut counts = [0usize; 243];
+        for (pattern, _) in &match_results {
+            counts[*pattern as usize] += 1;
+        }
+        let mut distribution: Vec<(MatchResult, f64, usize)> = (0..243)
+            .filter(|p| counts[*p] > 0)
+            .map(|p| (index_to_pattern(p), match_pattern_pd[p], counts[p]))
+            .collect();
+        distribution.sort_by(|a, b| b.1.total_cmp(&a.1).then(b.2.cmp(&a.2)));
+        distribution
+    }
+
+    /// Expected information, in bits, of guessing `word` now (whether or not it is in the
+    /// dictionary), or `None` if it isn't a five-letter word in the solver's alphabet
+    pub fn entropy_of(&self, word: &str) -> Option<f64> {
+        let guess = WordEncoding::from_string(word, &self.alphabet).ok()?;
+        let match_results: Vec<(Pattern, f64)> = self
+            .current_possibilities
+            .iter()
+            .map(|j| (guess.match_pattern(&self.dictionary[*j]), self.prior[*j]))
+            .collect();
+        Some(WordleSolver::compute_entropy(
+            WordleSolver::compute_match_pattern_pd(&match_results),
+        ))
+    }
+
+    /// How many remaining possibilities have each letter at each position:
+    /// `counts[position][letter]`, with letters indexed as in the solver's alphabet
+    pub fn position_counts(&self) -> [Vec<u32>; 5] {
+        let mut counts: [Vec<u32>; 5] = std::array::from_fn(|_| vec![0; self.alphabet.len()]);
+        for i in &self.current_possibilities {
+            for (position, c) in self.dictionary[*i].positions.iter().enumerate() {
+                counts[position][self.alphabet.index(*c).unwrap()] += 1;
+            }
+        }
+        counts
+    }
+
+    /// Positional letter frequency of each dictionary word, used to break ties between
+    /// equally good guesses (see `step()`)
+    pub fn letter_scores(&self) -> &[u32] {
+        &self.letter_scores
+    }
+
+    /// The prior probability of each dictionary word being the answer, indexed like
+    /// `dictionary`; 0 for words already ruled out
+    pub fn prior(&self) -> &[f64] {
+        &self.prior
+    }
+
+    /// Expected guesses still needed from here, as `MinimizeScore` estimates them: the
+    /// trained curve read at `log2` of the remaining possibilities, penalised past the
+    /// guesses left. `None` without a curve (see `estimate_expected_moves` for the untrained
+    /// estimate).
+    pub fn expected_guesses(&self) -> Option<f64> {
+        if self.expected_moves_curve.is_empty() {
+            return None;
+        }
+        let entropy = (self.current_possibilities.len() as f64).log2();
+        Some(self.compute_expected_score(entropy, self.guesses_left()))
+    }
+
+    /// Number of words still consistent with the feedback so far
+    pub fn remaining_count(&self) -> usize {
+        self.current_possibilities.len()
+    }
+
+    /// Indices of the dictionary words, in dictionary order, containing every letter of
+    /// `letters` in any position (a repeated letter must occur at least as often) and none
+    /// of `excluded`; with five `letters`, their anagrams. Letters outside the alphabet, or
+    /// given as both, are an error.
+    pub fn words_containing(&self, letters: &str, excluded: &str) -> Result<Vec<usize>, String> {
+        let mut required = vec![0u8; self.alphabet.len()];
+        let mut banned = vec![false; self.alphabet.len()];
+        let index = |c: char| {
+            self.alphabet
+                .index(c)
+                .ok_or_else(|| format!("'{}' is not in the alphabet", c))
+        };
+        for c in letters.chars() {
+            required[index(c)?] += 1;
+        }
+        for c in excluded.chars() {
+            let i = index(c)?;
+            if required[i] > 0 {
+                return Err(format!(
+                    "Letter {} can't be both required and excluded",
+                    Alphabet::normalise(c)
+                ));
+            }
+            banned[i] = true;
+        }
+
+        Ok((0..self.dictionary.len())
+            .filter(|i| self.dictionary[*i].contains_letters(&required, &banned))
+            .collect())
+    }
+
+    /// The words still consistent with the feedback so far, in dictionary order
+    pub fn candidates(&self) -> Vec<String> {
+        self.current_possibilities
+            .iter()
+            .map(|i| self.dictionary[*i].to_string())
+            .collect()
+    }
+
+    /// Each remaining possibility with its prior probability, most likely first
+    pub fn candidate_probabilities(&self) -> Vec<(String, f64)> {
+        let mut candidates: Vec<(String, f64)> = self
+            .current_possibilities
+            .iter()
+            .map(|i| (self.dictionary[*i].to_string(), self.prior[*i]))
+            .collect();
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        candidates
+    }
+
+    /// The `k` best guesses for the current state as `(word, entropy, expected score)`, best
+    /// first by the policy's one-ply objective, over the same guesses `step()` considers.
+    /// Ties go to remaining possibilities, then dictionary order. The expected score is
+    /// infinite unless the policy is `MinimizeScore` or `Blend`.
+    ///
+    /// Every guess is evaluated afresh, without pruning or lookahead, so this costs about as
+    /// much as an `exact` step; call it when a UI needs the alternatives, not every turn.
+    pub fn ranked_guesses(&self, k: usize) -> Vec<(String, f64, f64)> {
+        let mut is_candidate = vec![false; self.dictionary.len()];
+        for j in self.current_possibilities.iter() {
+            is_candidate[*j] = true;
+        }
+        let candidates_only = self.candidates_only();
+
+        let mut ranked: Vec<(usize, f64, f64, usize)> = self
+            .dictionary
+            .iter()
+            .enumerate()
+            .filter(|(i, guess)| {
+                !self.previous_guesses.contains(guess) && (!candidates_only || is_candidate[*i])
+            })
+            .map(|(i, guess)| {
+                let (match_results, _, entropy, expected_score) =
+                    self.evaluate_guess(Some(i), guess);
+                (i, entropy, expected_score, largest_group(&match_results))
+            })
+            .collect();
+
+        ranked.sort_by(|a, b| {
+            let objective = match self.policy {
+                Policy::MaximizeEntropy => b.1.total_cmp(&a.1),
+                Policy::MinimizeScore => a.2.total_cmp(&b.2),
+                Policy::MinimizeWorstCase => a.3.cmp(&b.3).then(b.1.total_cmp(&a.1)),
+                Policy::Blend { lambda } => (b.1 - lambda * b.2).total_cmp(&(a.1 - lambda * a.2)),
+            };
+            objective
+                .then(is_candidate[b.0].cmp(&is_candidate[a.0]))
+                .then(self.letter_scores[b.0].cmp(&self.letter_scores[a.0]))
+                .then(
+                    self.dictionary[a.0]
+                        .positions
+                        .cmp(&self.dictionary[b.0].positions),
+                )
+        });
+
+        ranked
+            .into_iter()
+            .take(k)
+            .map(|(i, entropy, expected_score, _)| {
+                (self.dictionary[i].to_string(), entropy, expected_score)
+            })
+            .collect()
+    }
+
+    /// Probability that the current guess is the answer: its prior if it is still possible,
+    /// otherwise 0 (as before any guess is chosen)
+    pub fn solve_probability(&self) -> f64 {
+        self.current_guess.as_ref().map_or(0.0, |guess| {
+            self.current_possibilities
+                .iter()
+                .find(|i| self.dictionary[**i] == *guess)
+                .map_or(0.0, |i| self.prior[*i])
+        })
+    }
+
+    /// Rough probability of winning within the guesses left in the budget, or `None` without
+    /// an expected-moves curve. The curve gives the mean number of moves `m` still needed
+    /// from the current entropy; the game takes at least one more, so the moves are modelled
+    /// as `1 + K` with `K` Poisson distributed with mean `m - 1`, and the probability is that
+    /// of `K` being below the budget.
+    pub fn win_probability(&self) -> Option<f64> {
+        let entropy = (self.current_possibilities.len() as f64).log2();
+        let moves = interp_expected_moves(&self.expected_moves_curve, entropy);
+        if moves.is_nan() {
+            return None; // no curve, or none of its buckets usable
+        }
+        let lambda = moves.max(1.0) - 1.0;
+
+        // P(K <= guesses_left - 1), summing the Poisson terms
+        let mut term = (-lambda).exp();
+        let mut cdf = 0.0;
+        for k in 0..self.guesses_left() {
+            cdf += term;
+            term *= lambda / (k + 1) as f64;
+        }
+        Some(cdf.min(1.0))
+    }
+
+    /// Explain the current guess: how finely it splits the remaining possibilities, how
+    /// likely it is to be the answer, and how it compares with the next best guess.
+    /// `None` until `step()` or `set_guess` has chosen a guess.
+    pub fn explain(&self) -> Option<GuessExplanation> {
+        let guess = self.current_guess.as_ref()?;
+        let match_results = self.current_guess_match_result.as_ref()?;
+
+        let mut seen = [false; 243];
+        for (pattern, _) in match_results {
+            seen[*pattern as usize] = true;
+        }
+        Some(GuessExplanation {
+            guess: guess.to_string(),
+            patterns: seen.iter().filter(|s| **s).count(),
+            largest_bucket: largest_group(match_results),
+            answer_probability: self.solve_probability(),
+            entropy: self.current_guess_entropy,
+            runner_up: self
+                .current_runner_up
+                .as_ref()
+                .map(|(word, entropy)| (word.to_string(), *entropy)),
+        })
+    }
+
+    /// What each feedback to the current guess would mean: the pattern, how many
+    /// possibilities it leaves, its probability under the prior and the entropy (in bits)
+    /// left among those possibilities, most likely first. The information a pattern
+    /// conveys is `-log2(probability)`. `None` until `step()` or `set_guess` has chosen a
+    /// guess.
+    pub fn risk_map(&self) -> Option<Vec<(MatchResult, usize, f64, f64)>> {
+        let match_results = self.current_guess_match_result.as_ref()?;
+
+        let mut counts = [0usize; 243];
+        let mut mass = [0.0; 243];
+        for (pattern, likelihood) in match_results {
+            counts[*pattern as usize] += 1;
+            mass[*pattern as usize] += likelihood;
+        }
+        // Entropy of the prior renormalised over each pattern's possibilities
+        let mut entropy = [0.0; 243];
+        for (pattern, likelihood) in match_results {
+            let p = likelihood / mass[*pattern as usize];
+            if p > 0.0 {
+                entropy[*pattern as usize] -= p * p.log2();
+            }
+        }
+        let total: f64 = mass.iter().sum();
+
+        let mut map: Vec<(MatchResult, usize, f64, f64)> = (0..243)
+            .filter(|p| counts[*p] > 0)
+            .map(|p| {
+                let probability = if total > 0.0 { mass[p] / total } else { 0.0 };
+                (index_to_pattern(p), counts[p], probability, entropy[p])
+            })
+            .collect();
+        map.sort_by(|a, b| b.2.total_cmp(&a.2).then(b.1.cmp(&a.1)));
+        Some(map)
+    }
+
+    /// Keep only the possibilities that satisfy `constraints`, without playing a guess. The
+    /// current guess is discarded since its match results no longer line up. Constraints
+    /// no possibility satisfies are rejected and leave the solver untouched.
+    pub fn filter_by_constraints(&mut self, constraints: &Constraints) -> Result<(), String> {
+        let keep: Vec<usize> = self
+            .current_possibilities
+            .iter()
+            .copied()
+            .filter(|i| constraints.matches(&self.dictionary[*i]))
+            .collect();
+        if keep.is_empty() {
+            return Err("No remaining word satisfies the constraints".to_string());
+        }
+        self.current_possibilities = keep;
+        self.clear_guess();
+
+        self.update_prior();
+        Ok(())
+    }
+
+    /// Snapshot the game in progress and the policy settings, so it can be resumed later by
+    /// `load_state` on a solver built from the same dictionary.
+    pub fn save_state(&self) -> SolverState {
+        SolverState {
+            dictionary_hash: self.dictionary_hash(),
+            policy: self.policy,
+            max_guesses: self.max_guesses,
+            start_word: self.start_word.clone(),
+            base_weights: self.base_weights.clone(),
+            exact: self.exact,
+            lookahead: self.lookahead,
+            hard_mode: self.hard_mode,
+            guess_threshold: self.guess_threshold,
+            commit_threshold: self.commit_threshold,
+            previous_guesses: self.previous_guesses.clone(),
+            information: self.information.clone(),
+            current_possibilities: self.current_possibilities.clone(),
+            prior: self.prior.clone(),
+            current_guess: self.current_guess.clone(),
+        }
+    }
+
+    /// Resume the game saved in `state`. A pending guess is restored too, so `guess()` can be
+    /// called straight away without another `step()`. Fails, leaving the solver untouched, if
+    /// the state was saved with a different dictionary or alphabet.
+    pub fn load_state(&mut self, state: SolverState) -> Result<(), String> {
+        let len = self.dictionary.len();
+        if state.dictionary_hash != self.dictionary_hash() {
+            return Err("state was saved with a different dictionary".to_string());
+        }
+        if state.prior.len() != len
+            || state.base_weights.len() != len
+            || state.current_possibilities.iter().any(|i| *i >= len)
+        {
+            return Err("state does not fit the dictionary".to_string());
+        }
+
+        self.policy = state.policy;
+        self.max_guesses = state.max_guesses;
+        self.start_word = state.start_word;
+        self.base_weights = state.base_weights;
+        self.exact = state.exact;
+        self.lookahead = state.lookahead;
+        self.hard_mode = state.hard_mode;
+        self.guess_threshold = state.guess_threshold;
+        self.commit_threshold = state.commit_threshold;
+        self.previous_guesses = state.previous_guesses;
+        self.information = state.information;
+        self.current_possibilities = state.current_possibilities;
+        self.prior = state.prior;
+        // Bounds from another game would prune wrongly; the next search recomputes them
+        self.entropy_bounds = vec![f64::INFINITY; len];
+
+        self.clear_guess();
+        if let Some(guess) = state.current_guess {
+            self.set_guess(guess);
+        }
+
+        Ok(())
+    }
+
+    // Between two guesses whose objectives tie, whether dictionary word `i` beats `best`:
+    // a remaining possibility first (it can win now), then the higher positional letter
+    // score, then the alphabetically smaller word
+    fn wins_tie(
+        &self,
+        i: usize,
+        i_is_candidate: bool,
+        best: usize,
+        best_is_candidate: bool,
+    ) -> bool {
+        if i_is_candidate != best_is_candidate {
+            return i_is_candidate;
+        }
+        match self.letter_scores[i].cmp(&self.letter_scores[best]) {
+            std::cmp::Ordering::Equal => {
+                self.dictionary[i].positions < self.dictionary[best].positions
+            }
+            order => order == std::cmp::Ordering::Greater,
+        }
+    }
+
+    // Whether only remaining possibilities may be guessed: whenever `candidates_required`,
+    // and once a candidate is known to be optimal
+    fn candidates_only(&self) -> bool {
+        self.candidates_required() || self.has_separating_candidate()
+    }
+
+    // Whether a rule restricts guesses to remaining possibilities: hard mode, the last guess
+    // (a non-candidate can't win) and the guess threshold
+    fn candidates_required(&self) -> bool {
+        self.hard_mode || self.guesses_left() == 1 || self.below_guess_threshold()
+    }
+
+    // Whether `MinimizeScore` has dropped below `guess_threshold` bits of remaining entropy
+    fn below_guess_threshold(&self) -> bool {
+        self.policy == Policy::MinimizeScore
+            && (self.current_possibilities.len() as f64).log2() < self.guess_threshold
+    }
+
+    // Whether, with at most `ENDGAME_THRESHOLD` possibilities left, one of them gives a
+    // different pattern against every other. Guessing it is optimal: it wins now or leaves a
+    // single word, and no guess can do better than that.
+    fn has_separating_candidate(&self) -> bool {
+        if self.current_possibilities.len() > ENDGAME_THRESHOLD {
+            return false;
+        }
+        self.current_possibilities.iter().any(|&i| {
+            let guess = &self.dictionary[i];
+            let mut patterns: Vec<Pattern> = self
+                .current_possibilities
+                .iter()
+                .map(|&j| guess.match_pattern(&self.dictionary[j]))
+                .collect();
+            patterns.sort_unstable();
+            patterns.dedup();
+            !self.previous_guesses.contains(guess)
+                && patterns.len() == self.current_possibilities.len()
+        })
+    }
+
+    /// Use `guess` as the current guess instead of the one chosen by `step()`, e.g. when the
+    /// player typed their own word. The following `guess()` call filters against it.
+    pub fn set_guess(&mut self, guess: WordEncoding) {
+        self.store_guess(self.rate_guess(guess));
+    }
+
+    // Make `info` the current guess
+    fn store_guess(&mut self, info: GuessInfo) {
+        self.current_guess = Some(info.word);
+        self.current_guess_entropy = info.entropy;
+        self.current_guess_worst_case = info.worst_case;
+        self.current_guess_match_result = Some(info.match_results);
+        self.current_guess_match_pattern_pd = Some(info.match_pattern_pd);
+        self.current_expected_score = info.expected_score;
+        self.current_runner_up = info.runner_up;
+    }
+
+    // How `guess` rates against the current possibilities, with no runner-up
+    fn rate_guess(&self, guess: WordEncoding) -> GuessInfo {
+        let index = self.dictionary.iter().position(|word| *word == guess);
+        let (match_results, match_pattern_pd, entropy, expected_score) =
+            self.evaluate_guess(index, &guess);
+        GuessInfo {
+            word: guess,
+            entropy,
+            expected_score,
+            worst_case: largest_group(&match_results),
+            runner_up: None,
+            match_results,
+            match_pattern_pd,
+        }
+    }
+
+    // Match results, pattern distribution, entropy and expected score of `guess` against the
+    // current possibilities. `i` is the guess' dictionary index, if it has one (otherwise it
+    // carries no prior mass).
+    fn evaluate_guess(
+        &self,
+        i: Option<usize>,
+        guess: &WordEncoding,
+    ) -> (Vec<(Pattern, f64)>, [f64; 243], f64, f64) {
+        let mut match_results: Vec<(Pattern, f64)> =
+            Vec::with_capacity(self.current_possibilities.len());
+
+        for j in self.current_possibilities.iter() {
+            let match_pattern = guess.match_pattern(&self.dictionary[*j]);
+            match_results.push((match_pattern, self.prior[*j]))
+        }
+
+        let match_pattern_pd = WordleSolver::compute_match_pattern_pd(&match_results);
+        let entropy = WordleSolver::compute_entropy(match_pattern_pd);
+
+        let expected_score = match self.policy {
+            Policy::MaximizeEntropy | Policy::MinimizeWorstCase => f64::INFINITY,
+            Policy::MinimizeScore | Policy::Blend { .. } => {
+                // We really need to punish when the prior is zero - we only want to explore when prior is zero
+                let prior = i.map_or(0.0, |i| self.prior[i]);
+                1.0 + (1.0 - prior)
+                    * self.compute_expected_score(
+                        (self.current_possibilities.len() as f64).log2() - entropy,
+                        self.guesses_left().saturating_sub(1),
+                    )
+            }
+        };
+
+        (match_results, match_pattern_pd, entropy, expected_score)
+    }
+
+    fn compute_word_encodings(
+        words: &[String],
+        alphabet: &Alphabet,
+    ) -> Result<Vec<WordEncoding>, String> {
+        let mut encodings: Vec<WordEncoding> = Vec::new();
+
+        // Compute encoding for each word
+        for word in words {
+            let encoding = WordEncoding::from_string(word, alphabet)?;
+            encodings.push(encoding);
+        }
+
+        Ok(encodings)
+    }
+
+    // Compute the 'match pattern' probability distribution (pd), of a given word over the possibility
+    pub fn compute_match_pattern_pd(match_results: &[(Pattern, f64)]) -> [f64; 243] {
+        let mut sum: f64 = 0.0;
+        let mut match_pattern_pd: [f64; 243] = [0.0; 243];
+
+        for (pattern, likelihood) in match_results {
+            match_pattern_pd[*pattern as usize] += likelihood;
+            sum += likelihood;
+        }
+
+        // Normalise. With no (or only zero-likelihood) results there is nothing to normalise,
+        // and the all-zero distribution is left as is (entropy 0) rather than becoming NaN.
+        if sum > 0.0 {
+            for x in match_pattern_pd.iter_mut() {
+                *x /= sum;
+            }
+        }
+
+        match_pattern_pd
+    }
+
+    pub fn compute_entropy<const N: usize>(pd: [f64; N]) -> f64 {
+        WordleSolver::compute_entropy_in(pd, EntropyUnit::Bits)
+    }
+
+    /// Entropy of the distribution `pd` in `unit`
+    pub fn compute_entropy_in<const N: usize>(pd: [f64; N], unit: EntropyUnit) -> f64 {
+        let mut entropy: f64 = 0.0;
+        for probabilty in pd.iter() {
+            if *probabilty > 0.0 {
+                entropy += -1.0 * (*probabilty) * unit.log(*probabilty);
+            }
+        }
+
+        entropy
+    }
+
+    // Expected moves still needed after a guess that leaves `entropy` bits and doesn't solve
+    // the game, with `budget` guesses left afterwards: read off the trained curve, or from
+    // `estimate_expected_moves` before any training (or when the curve has no usable
+    // bucket, so a NaN never reaches the score). Estimates running past the budget are
+    // penalised so guaranteed solves win near the end of the game.
+    fn compute_expected_score(&self, entropy: f64, budget: usize) -> f64 {
+        let moves = match interp_expected_moves(&self.expected_moves_curve, entropy) {
+            moves if moves.is_nan() => estimate_expected_moves(entropy),
+            moves => moves,
+        };
+
+        // An unsolved game needs at least one more guess
+        let overrun = (moves.max(1.0) - budget as f64).max(0.0);
+        moves + BUDGET_OVERRUN_PENALTY * overrun
+    }
+}
+
+/// Solves several boards at once (Dordle, Quordle, ...): every guess is played on all boards,
+/// and each board narrows its own possibilities from its own feedback.
+#[derive(Clone)]
+pub struct MultiSolver {
+    pub boards: Vec<WordleSolver>, // One solver per board, all sharing the same dictionary
+    pub current_guess: Option<WordEncoding>,
+    pub current_guess_entropy: f64, // Entropy of the current guess summed over unsolved boards
+    previous_guesses: Vec<WordEncoding>,
+    solved: Vec<bool>, // Whether each board has had all-M feedback
+}
+
+impl MultiSolver {
+    /// Create `boards` boards from `solver`. The clones share its dictionary.
+    pub fn new(solver: WordleSolver, boards: usize) -> MultiSolver {
+        MultiSolver {
+            boards: vec![solver; boards],
+            current_guess: None,
+            current_guess_entropy: 0.0,
+            previous_guesses: Vec::new(),
+            solved: vec![false; boards],
+        }
+    }
+
+    /// A board is solved once its answer has been guessed, i.e. it got all-M feedback. A
+    /// board down to one possibility still needs that word guessed; until then it only
+    /// offers the word as a candidate in `step()`.
+    pub fn is_solved(&self, board: usize) -> bool {
+        self.solved[board]
+    }
+
+    pub fn all_solved(&self) -> bool {
+        (0..self.boards.len()).all(|board| self.is_solved(board))
+    }
+
+    /// Pick the guess with the highest entropy summed over the unsolved boards. Ties are
+    /// broken like `WordleSolver::step()`: a word still possible on some unsolved board
+    /// first, then the alphabetically smaller word.
+    pub fn step(&mut self) -> Result<WordEncoding, SolverError> {
+        self.current_guess = None;
+        self.current_guess_entropy = 0.0;
+        if self
+            .boards
+            .iter()
+            .any(|board| board.current_possibilities.is_empty())
+        {
+            return Err(SolverError::NoCandidates);
+        }
+
+        let unsolved: Vec<&WordleSolver> = (0..self.boards.len())
+            .filter(|&board| !self.is_solved(board))
+            .map(|board| &self.boards[board])
+            .collect();
+        let Some(first) = unsolved.first() else {
+            return Err(SolverError::Exhausted);
+        };
+
+        let mut is_candidate = vec![false; first.dictionary.len()];
+        for board in &unsolved {
+            for j in board.current_possibilities.iter() {
+                is_candidate[*j] = true;
+            }
+        }
+        let mut current_index: Option<usize> = None;
+        let mut current_is_candidate = false;
+
+        for (i, guess) in first.dictionary.iter().enumerate() {
+            if self.previous_guesses.contains(guess) {
+                continue;
+            }
+
+            let entropy: f64 = unsolved
+                .iter()
+                .map(|board| board.evaluate_guess(Some(i), guess).2)
+                .sum();
+            let gain = entropy - self.current_guess_entropy;
+
+            let is_better = match current_index {
+                None => true,
+                Some(_) if gain > TIE_EPSILON => true,
+                Some(_) if gain < -TIE_EPSILON => false,
+                Some(best) => first.wins_tie(i, is_candidate[i], best, current_is_candidate),
+            };
+
+            if is_better {
+                current_index = Some(i);
+                self.current_guess = Some(guess.clone());
+                self.current_guess_entropy = entropy;
+                current_is_candidate = is_candidate[i];
+            }
+        }
+
+        self.current_guess.clone().ok_or(SolverError::Exhausted)
+    }
+
+    /// Play the current guess on every unsolved board. `callback` receives the board index
+    /// and the guess, and returns that board's feedback. If any board's feedback is
+    /// contradictory, no board is updated.
+    pub fn guess<CheckFunction>(&mut self, callback: CheckFunction) -> Result<(), SolverError>
+    where
+        CheckFunction: Fn(usize, &WordEncoding) -> MatchResult,
+    {
+        let Some(guess) = self.current_guess.clone() else {
+            return Ok(());
+        };
+
+        let mut feedback = Vec::new();
+        for (board, solver) in self.boards.iter().enumerate() {
+            if self.is_solved(board) {
+                continue;
+            }
+            let result = callback(board, &guess);
+            let consistent = solver
+                .current_possibilities
+                .iter()
+                .any(|i| guess.match_pattern(&solver.dictionary[*i]) == pack_pattern(&result));
+            if !consistent {
+                return Err(SolverError::NoCandidates);
+            }
+            feedback.push((board, result));
+        }
+
+        self.previous_guesses.push(guess.clone());
+        for (board, result) in feedback {
+            self.boards[board].set_guess(guess.clone());
+            self.boards[board].guess(|_| result)?;
+            self.solved[board] = is_solved(&result);
+        }
+        Ok(())
+    }
+
+    pub fn reset(&mut self) {
+        self.current_guess = None;
+        self.current_guess_entropy = 0.0;
+        self.previous_guesses.clear();
+        self.solved.fill(false);
+        for board in self.boards.iter_mut() {
+            board.reset();
+        }
+    }
+}
+
+// Positional letter frequency of each word: for each position, the number of dictionary
+// words with the same letter there, summed. Words spelled like typical words score high.
+fn positional_letter_scores(dictionary: &[WordEncoding]) -> Vec<u32> {
+    let mut counts: [HashMap<char, u32>; 5] = Default::default();
+    for word in dictionary {
+        for (position, c) in word.positions.iter().enumerate() {
+            *counts[position].entry(*c).or_insert(0) += 1;
+        }
+    }
+    dictionary
+        .iter()
+        .map(|word| {
+            word.positions
+                .iter()
+                .enumerate()
+                .map(|(position, c)| counts[position][c])
+                .sum()
+        })
+        .collect()
+}
+
+// Base weight of each dictionary word by rank: a sigmoid that keeps the most common words
+// (the start of the dictionary) about equally likely and fades out the rare ones
+fn rank_weights(len: usize) -> Vec<f64> {
+    let parametric_sigmoid = |x: f64, midpoint: f64, steepness: f64| -> f64 {
+        1.0 / (1.0 + (steepness * (x - midpoint)).exp())
+    };
+    (0..len)
+        .map(|w| parametric_sigmoid(w as f64, RANK_PRIOR_MIDPOINT, RANK_PRIOR_STEEPNESS))
+        .collect()
+}
+
+// The `word,value` pairs of a CSV file, keyed by upper-cased word. Lines without a numeric
+// value (e.g. a header) are skipped, and negative values are taken as 0.
+#[cfg(not(target_arch = "wasm32"))]
+fn read_word_values(path: &Path) -> io::Result<HashMap<String, f64>> {
+    let file = File::open(path)?;
+    let mut values: HashMap<String, f64> = HashMap::new();
+    for line in io::BufReader::new(file).lines() {
+        let line = line?;
+        let Some((word, value)) = line.split_once(',') else {
+            continue;
+        };
+        if let Ok(value) = value.trim().parse::<f64>()
+            && value.is_finite()
+        {
+            // Spelled as dictionary words are; `to_uppercase` would turn ß into SS
+            let key: String = word.trim().chars().map(Alphabet::normalise).collect();
+            values.insert(key, value.max(0.0));
+        }
+    }
+    Ok(values)
+}
+
+/// The words of the dictionary file `dictionary`, most common first by the `word,count`
+/// file `frequencies` (read as by `WordleSolver::load_frequencies`), so the rank prior gets
+/// a properly ranked list. Words without a count follow in their original order. Returns
+/// the words and how many of them had a count.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn sort_dictionary_by_frequency(
+    dictionary: &Path,
+    frequencies: &Path,
+) -> io::Result<(Vec<String>, usize)> {
+    let mut words = parse_dictionary(&std::fs::read(dictionary)?);
+    let counts = read_word_values(frequencies)?;
+    let count = |word: &str| {
+        let key: String = word.chars().map(Alphabet::normalise).collect();
+        counts.get(&key).copied()
+    };
+
+    // A stable sort keeps ties, and the words without a count, in dictionary order
+    words.sort_by(|a, b| match (count(a), count(b)) {
+        (Some(a), Some(b)) => b.total_cmp(&a),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+    let found = words.iter().filter(|word| count(word).is_some()).count();
+    Ok((words, found))
+}
+
+// Split `possibilities` (dictionary indices) by the pattern `guess` gets against each
+fn group_by_pattern(
+    guess: &WordEncoding,
+    possibilities: &[usize],
+    dictionary: &[WordEncoding],
+) -> Vec<(Pattern, Vec<usize>)> {
+    let mut groups: Vec<(Pattern, Vec<usize>)> = Vec::new();
+    for i in possibilities {
+        let pattern = guess.match_pattern(&dictionary[*i]);
+        match groups.iter_mut().find(|(p, _)| *p == pattern) {
+            Some((_, group)) => group.push(*i),
+            None => groups.push((pattern, vec![*i])),
+        }
+    }
+    groups
+}
+
+// Size of the largest group of `match_results` sharing a pattern
+fn largest_group(match_results: &[(Pattern, f64)]) -> usize {
+    let mut counts = [0usize; 243];
+    for (pattern, _) in match_results {
+        counts[*pattern as usize] += 1;
+    }
+    counts.into_iter().max().unwrap_or(0)
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Bucket {
+    pub centre: f64,    // bucket midpoint (x axis)
+    pub avg_moves: f64, // average moves‑remaining in this bucket
+}
+
+// First line of every training/test shard. `solved` is 1 when the game was won and 0 when
+// it ran out of guesses first.
+pub const SHARD_HEADER: &str = "secret_idx,entropy,moves_remaining,solved";
+
+// One row of a shard: the state before a guess and the moves it took from there
+#[cfg(not(target_arch = "wasm32"))]
+struct ShardRow {
+    secret_idx: usize,
+    entropy: f64,
+    moves: f64,
+    solved: bool, // Whether the row's game was won; rows from before the column count as won
+}
+
+// The row on a shard line, or None if the line is malformed
+#[cfg(not(target_arch = "wasm32"))]
+fn parse_shard_row(line: &str) -> Option<ShardRow> {
+    let mut it = line.split(',');
+    let secret_idx: usize = it.next()?.trim().parse().ok()?;
+    let entropy: f64 = it.next()?.trim().parse().ok()?;
+    let moves: f64 = it.next()?.trim().parse().ok()?;
+    let solved = match it.next().map(str::trim) {
+        None | Some("1") => true,
+        Some("0") => false,
+        Some(_) => return None,
+    };
+    if it.next().is_some() || !entropy.is_finite() || !moves.is_finite() {
+        return None;
+    }
+    Some(ShardRow {
+        secret_idx,
+        entropy,
+        moves,
+        solved,
+    })
+}
+
+/// Rewrite the shard at `path` in the current format if it was written before the `solved`
+/// column: the header gains the column and every row a 1, since only won games were
+/// recorded then. Malformed rows are copied as they are. Returns whether the shard was
+/// rewritten; a missing shard or one in the current format is left alone.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn upgrade_legacy_shard(path: &Path) -> io::Result<bool> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e),
+    };
+    let mut lines = text.lines();
+    let Some(unit) = lines
+        .next()
+        .and_then(|header| EntropyUnit::of_legacy_shard_header(header.trim()))
+    else {
+        return Ok(false);
+    };
+
+    let tmp_path = path.with_extension(format!("tmp.{}", std::process::id()));
+    let mut writer = BufWriter::new(File::create(&tmp_path)?);
+    writeln!(writer, "{}", unit.shard_header())?;
+    for line in lines {
+        match parse_shard_row(line) {
+            Some(_) if line.split(',').count() == 3 => writeln!(writer, "{},1", line.trim_end())?,
+            _ => writeln!(writer, "{line}")?,
+        }
+    }
+    writer.flush()?;
+    drop(writer);
+
+    std::fs::rename(tmp_path, path)?;
+    Ok(true)
+}
+
+// Paths of the shards matching `glob_pattern`, in glob order
+#[cfg(not(target_arch = "wasm32"))]
+fn shard_paths(glob_pattern: &str) -> io::Result<Vec<PathBuf>> {
+    glob(glob_pattern)
+        .map_err(io::Error::other)?
+        .map(|entry| entry.map_err(io::Error::other))
+        .collect()
+}
+
+// Call `f` with every row of the shards at `paths`, in order, and return how many
+// malformed rows were skipped. Entropies are converted to bits from the unit named in each
+// shard's header (see `EntropyUnit::shard_header`).
+//
+// Shards from an interrupted run are tolerated: empty shards and shards with an unexpected
+// header are skipped, and malformed rows (e.g. a truncated last line) are skipped with a
+// warning. Only a shard that can't be read at all is an error.
+#[cfg(not(target_arch = "wasm32"))]
+fn for_each_row_in(paths: &[PathBuf], mut f: impl FnMut(ShardRow)) -> io::Result<usize> {
+    let mut total_malformed = 0;
+    for path in paths {
+        let with_path = |e: io::Error| io::Error::new(e.kind(), format!("{}: {e}", path.display()));
+
+        let file = File::open(path).map_err(with_path)?;
+        let mut lines = io::BufReader::new(file).lines();
+
+        let unit = match lines.next().transpose().map_err(with_path)? {
+            None => continue, // interrupted before the header was written
+            Some(header) => match EntropyUnit::of_shard_header(header.trim()) {
+                Some(unit) => unit,
+                None => {
+                    note!(
+                        "⚠️  Skipping {}: expected header '{}', found '{}'",
+                        path.display(),
+                        SHARD_HEADER,
+                        header.trim()
+                    );
+                    continue;
+                }
+            },
+        };
+
+        let mut malformed = 0;
+        for line in lines {
+            let line = line.map_err(with_path)?;
+            match parse_shard_row(&line) {
+                Some(row) => f(ShardRow {
+                    entropy: unit.to_bits(row.entropy),
+                    ..row
+                }),
+                None => malformed += 1,
+            }
+        }
+        if malformed > 0 {
+            note!(
+                "⚠️  Skipped {} malformed row(s) in {}",
+                malformed,
+                path.display()
+            );
+        }
+        total_malformed += malformed;
+    }
+
+    Ok(total_malformed)
+}
+
+/// What `merge_shards` found
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, Copy)]
+pub struct MergeSummary {
+    pub shards: usize,
+    pub rows: usize,
+    pub malformed: usize,
+}
+
+/// Concatenate every shard matching `glob_pattern` into one shard at `out`, header first.
+/// Rows are parsed as for `build_moves_histogram`, so malformed ones are reported and left
+/// out rather than copied. `out` may not be one of the shards being merged.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn merge_shards(glob_pattern: &str, out: &Path) -> io::Result<MergeSummary> {
+    let paths = shard_paths(glob_pattern)?;
+    if let Ok(target) = out.canonicalize()
+        && paths
+            .iter()
+            .any(|p| p.canonicalize().is_ok_and(|p| p == target))
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is one of the shards being merged", out.display()),
+        ));
+    }
+
+    let mut writer = BufWriter::new(File::create(out)?);
+    writeln!(writer, "{}", SHARD_HEADER)?;
+
+    let mut rows = 0;
+    let mut written = Ok(());
+    let malformed = for_each_row_in(&paths, |row| {
+        if written.is_ok() {
+            written = writeln!(
+                writer,
+                "{},{},{},{}",
+                row.secret_idx,
+                row.entropy,
+                row.moves,
+                u8::from(row.solved)
+            );
+            rows += 1;
+        }
+    })?;
+    written?;
+    writer.flush()?;
+
+    Ok(MergeSummary {
+        shards: paths.len(),
+        rows,
+        malformed,
+    })
+}
+
+// Every game in the shards at `paths`, keyed by secret. A game starts on a new
+// secret, or when moves remaining stop counting down; when a secret was played more than
+// once (e.g. a resumed run overlapping an earlier one), only its last game is kept.
+#[cfg(not(target_arch = "wasm32"))]
+fn last_game_per_secret(paths: &[PathBuf]) -> io::Result<BTreeMap<usize, Vec<ShardRow>>> {
+    let mut games: BTreeMap<usize, Vec<ShardRow>> = BTreeMap::new();
+    let mut previous: Option<(usize, f64)> = None;
+
+    for_each_row_in(paths, |row| {
+        let starts_game = match previous {
+            None => true,
+            Some((secret_idx, moves)) => secret_idx != row.secret_idx || row.moves >= moves,
+        };
+        previous = Some((row.secret_idx, row.moves));
+        let game = games.entry(row.secret_idx).or_default();
+        if starts_game {
+            game.clear();
+        }
+        game.push(row);
+    })?;
+
+    Ok(games)
+}
+
+/// Average moves remaining per entropy bucket over every shard matching `glob_pattern`,
+/// counting each secret's last game once. Unreadable shards are an error; interrupted ones
+/// are tolerated as far as possible.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn build_moves_histogram(glob_pattern: &str, bucket_width: f64) -> io::Result<Vec<Bucket>> {
+    let mut sum_moves: HashMap<i64, f64> = HashMap::new();
+    let mut counts: HashMap<i64, usize> = HashMap::new();
+
+    for row in last_game_per_secret(&shard_paths(glob_pattern)?)?
+        .values()
+        .flatten()
+    {
+        let idx = (row.entropy / bucket_width).floor() as i64;
+        *sum_moves.entry(idx).or_insert(0.0) += row.moves;
+        *counts.entry(idx).or_insert(0) += 1;
+    }
+
+    let mut buckets: Vec<Bucket> = sum_moves
+        .into_iter()
+        .map(|(idx, sum)| {
+            let count = counts[&idx];
+            Bucket {
+                centre: (idx as f64 + 0.5) * bucket_width,
+                avg_moves: sum / count as f64,
+            }
+        })
+        .collect();
+
+    buckets.sort_by(|a, b| a.centre.total_cmp(&b.centre));
+    Ok(buckets)
+}
+
+/// Number of guesses each secret took, reconstructed from the shards matching
+/// `glob_pattern`, or `None` for a secret whose game ran out of guesses unsolved. A game's
+/// first row holds its total, since moves remaining count down to 1 on the solving guess.
+/// When a secret was played more than once, its last game counts.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn shard_guess_counts(glob_pattern: &str) -> io::Result<HashMap<usize, Option<usize>>> {
+    guess_counts_in(&shard_paths(glob_pattern)?)
+}
+
+/// `shard_guess_counts` of the single shard at `path`, taken literally rather than as a
+/// glob pattern, so e.g. a directory named with `[` still finds the shard.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn shard_file_guess_counts(path: &Path) -> io::Result<HashMap<usize, Option<usize>>> {
+    guess_counts_in(&[path.to_path_buf()])
+}
+
+// Guesses per secret over the shards at `paths`, see `shard_guess_counts`
+#[cfg(not(target_arch = "wasm32"))]
+fn guess_counts_in(paths: &[PathBuf]) -> io::Result<HashMap<usize, Option<usize>>> {
+    Ok(last_game_per_secret(paths)?
+        .into_iter()
+        .map(|(secret_idx, game)| {
+            let guesses = game[0].solved.then_some(game[0].moves as usize);
+            (secret_idx, guesses)
+        })
+        .collect())
+}
+
+/// Closed-form expected moves left with `entropy` bits of uncertainty, used by `MinimizeScore`
+/// when no trained curve exists: `1 + UNTRAINED_MOVES_SLOPE * log2(1 + entropy)`.
+///
+/// One word left (0 bits) takes exactly one more guess. Two equally likely words (1 bit)
+/// take 1.5 on average, which this puts at 1.55. Beyond that each guess splits the
+/// possibilities into many groups, so the moves grow much more slowly than the entropy; the
+/// slope is fitted to trained curves, and gives about 2.9 moves for a 1000-word list
+/// (10 bits), a little under the 3.1 a trained curve gives there.
+pub fn estimate_expected_moves(entropy: f64) -> f64 {
+    1.0 + UNTRAINED_MOVES_SLOPE * (1.0 + entropy.max(0.0)).log2()
+}
+
+/// Expected moves at `entropy`, interpolated linearly between the centres of `buckets`
+/// (sorted by centre, as `build_moves_histogram` returns them).
+///
+/// Outside the curve the value is extrapolated flat: below the first centre it is the first
+/// bucket's average, above the last centre (or for a NaN `entropy`) the last bucket's.
+/// Buckets with a non-finite centre or average are ignored, and a bucket whose centre isn't
+/// past the previous one (a zero-width interval) is merged into it, averaging them, so
+/// the interpolation never divides by zero. Returns NaN only when no usable bucket is left.
+pub fn interp_expected_moves(buckets: &[Bucket], entropy: f64) -> f64 {
+    let mut usable = buckets
+        .iter()
+        .copied()
+        .filter(|b| b.centre.is_finite() && b.avg_moves.is_finite())
+        .peekable();
+    // The next point of the curve: a bucket merged with the ones after it not past its centre
+    let mut next_point = || {
+        let mut point = usable.next()?;
+        let mut merged = 1.0;
+        while let Some(b) = usable.next_if(|b| b.centre <= point.centre) {
+            merged += 1.0;
+            point.avg_moves += (b.avg_moves - point.avg_moves) / merged;
+        }
+        Some(point)
+    };
+
+    let Some(mut left) = next_point() else {
+        return f64::NAN;
+    };
+    if entropy <= left.centre {
+        return left.avg_moves;
+    }
+    while let Some(right) = next_point() {
+        if entropy <= right.centre {
+            let t = (entropy - left.centre) / (right.centre - left.centre);
+            return left.avg_moves + t * (right.avg_moves - left.avg_moves);
+        }
+        left = right;
+    }
+    left.avg_moves
+}
+
+/// Parse constraints written as space separated terms:
+/// * `__E__`: known letters by position, `_` or `.` where unknown
+/// * `+AT`: letters in the word; repeat a letter for a minimum count (`+EE`)
+/// * `-RS`: letters not in the word
+pub fn parse_constraints(spec: &str) -> Result<Constraints, String> {
+    let mut constraints = Constraints::default();
+
+    for term in spec.split_whitespace() {
+        let term = term.to_uppercase();
+        let letters = |s: &str| -> Result<Vec<char>, String> {
+            match s.chars().all(|c| c.is_alphabetic()) && !s.is_empty() {
+                true => Ok(s.chars().collect()),
+                false => Err(format!("Expected letters after '+' or '-', got: {}", term)),
+            }
+        };
+
+        if let Some(rest) = term.strip_prefix('+') {
+            for c in letters(rest)? {
+                let count = rest.chars().filter(|r| *r == c).count() as u8;
+                match constraints.present.iter_mut().find(|(p, _)| *p == c) {
+                    Some((_, n)) => *n = (*n).max(count),
+                    None => constraints.present.push((c, count)),
+                }
+            }
+        } else if let Some(rest) = term.strip_prefix('-') {
+            for c in letters(rest)? {
+                if !constraints.absent.contains(&c) {
+                    constraints.absent.push(c);
+                }
+            }
+        } else if term.chars().count() == 5
+            && term
+                .chars()
+                .all(|c| c.is_alphabetic() || c == '_' || c == '.')
+        {
+            for (green, c) in constraints.greens.iter_mut().zip(term.chars()) {
+                if c.is_alphabetic() {
+                    *green = Some(c);
+                }
+            }
+        } else {
+            return Err(format!(
+                "Unrecognised constraint '{}' (use e.g. __E__ +AT -R)",
+                term
+            ));
+        }
+    }
+
+    if let Some(c) = constraints.absent.iter().find(|c| {
+        constraints.greens.contains(&Some(**c)) || constraints.present.iter().any(|(p, _)| p == *c)
+    }) {
+        return Err(format!("Letter {} can't be both present and absent", c));
+    }
+
+    Ok(constraints)
+}
+
+// The ways of writing feedback `parse_feedback` accepts
+#[derive(PartialEq, Clone, Copy)]
+enum FeedbackScheme {
+    Letters,
+    Digits,
+    Emoji,
+}
+
+impl FeedbackScheme {
+    // The tile characters of the scheme for Match, Partial and NoMatch; emoji NoMatch is
+    // also written white (⬜)
+    fn tiles(self) -> [char; 3] {
+        match self {
+            FeedbackScheme::Letters => ['M', 'P', 'N'],
+            FeedbackScheme::Digits => ['2', '1', '0'],
+            FeedbackScheme::Emoji => ['🟩', '🟨', '⬛'],
+        }
+    }
+
+    // Scheme and kind of a single feedback tile
+    fn tile(c: char) -> Option<(FeedbackScheme, MatchKind)> {
+        Some(match c {
+            'M' => (FeedbackScheme::Letters, MatchKind::Match),
+            'P' => (FeedbackScheme::Letters, MatchKind::Partial),
+            'N' => (FeedbackScheme::Letters, MatchKind::NoMatch),
+            '2' => (FeedbackScheme::Digits, MatchKind::Match),
+            '1' => (FeedbackScheme::Digits, MatchKind::Partial),
+            '0' => (FeedbackScheme::Digits, MatchKind::NoMatch),
+            '🟩' => (FeedbackScheme::Emoji, MatchKind::Match),
+            '🟨' => (FeedbackScheme::Emoji, MatchKind::Partial),
+            '⬛' | '⬜' => (FeedbackScheme::Emoji, MatchKind::NoMatch),
+            _ => return None,
+        })
+    }
+}
+
+// Drop the variation selectors pasted squares often carry ("⬛\u{FE0F}")
+fn strip_variation_selectors(feedback: &str) -> String {
+    feedback.chars().filter(|c| *c != '\u{FE0F}').collect()
+}
+
+/// Parse the user feedback string into MatchResult.
+///
+/// Three schemes are accepted and auto-detected from the input:
+/// * letters: "MPNPN" (M = Match, P = Partial, N = No match)
+/// * digits:  "21010" (2 = green/Match, 1 = yellow/Partial, 0 = gray/No match)
+/// * emoji:   "🟩🟨⬛🟨⬛" as copied from the game (⬛ or ⬜ = No match)
+///
+/// Mixing schemes in one string is rejected.
+pub fn parse_feedback(feedback: &str) -> Result<MatchResult, String> {
+    let feedback = strip_variation_selectors(feedback);
+    let length = feedback.chars().count();
+    if length != 5 {
+        return Err(format!(
+            "Feedback must be exactly 5 characters (M/P/N, 2/1/0 or 🟩/🟨/⬛). Got {} characters: {}",
+            length, feedback
+        ));
+    }
+
+    let mut scheme = None;
+    let mut result = [MatchKind::NoMatch; 5];
+    for (i, c) in feedback.chars().enumerate() {
+        result[i] = match (FeedbackScheme::tile(c), scheme) {
+            (Some((found, kind)), None) => {
+                scheme = Some(found);
+                kind
+            }
+            (Some((found, kind)), Some(expected)) if found == expected => kind,
+            (Some(_), Some(_)) => {
+                return Err(format!(
+                    "Mixed feedback schemes in '{}'. Use one of M/P/N, 2/1/0 or 🟩/🟨/⬛, not a mix.",
+                    feedback
+                ));
+            }
+            (None, scheme) => {
+                let [m, p, n] = scheme.unwrap_or(FeedbackScheme::Letters).tiles();
+                return Err(format!(
+                    "Invalid feedback character '{}'. Use only {}, {}, {}.",
+                    c, m, p, n
+                ));
+            }
+        }
+    }
+    Ok(result)
+}
+
+// Most `?` tiles `parse_uncertain_feedback` accepts; each one triples the patterns to match
+pub const MAX_UNKNOWN_TILES: usize = 2;
+
+/// Parse feedback in which `?` marks a tile whose colour is unknown, e.g. "MP?NN", into
+/// every pattern it could be. Trailing tiles may also be left off when only the first few
+/// are remembered, e.g. "MPN", and are unknown too; at least one tile must be known, so
+/// there are at most 81 patterns. Known tiles are read as by `parse_feedback`, in any
+/// scheme; a full row without a `?` gives a single pattern.
+pub fn parse_uncertain_feedback(feedback: &str) -> Result<Vec<MatchResult>, String> {
+    let mut feedback = strip_variation_selectors(feedback);
+    let given = feedback.chars().count();
+    if given == 0 || given > 5 {
+        return parse_feedback(&feedback).map(|pattern| vec![pattern]); // reports the length
+    }
+    let unknown = feedback.chars().filter(|c| *c == '?').count();
+    if unknown > MAX_UNKNOWN_TILES {
+        return Err(format!(
+            "At most {} tiles may be unknown ('?'). Got {}: {}",
+            MAX_UNKNOWN_TILES, unknown, feedback
+        ));
+    }
+    if unknown == given {
+        return Err(format!(
+            "At least one tile must be known. Got: {}",
+            feedback
+        ));
+    }
+    // The tiles left off are unknown
+    feedback.extend(std::iter::repeat_n('?', 5 - given));
+    let unknown = unknown + 5 - given;
+
+    // Fill the unknown tiles in the scheme of the known ones, one combination at a time
+    let kinds = feedback
+        .chars()
+        .find_map(FeedbackScheme::tile)
+        .map_or(FeedbackScheme::Letters, |(scheme, _)| scheme)
+        .tiles();
+    (0..3usize.pow(unknown as u32))
+        .map(|combination| {
+            let mut digits = combination;
+            let filled: String = feedback
+                .chars()
+                .map(|c| match c {
+                    '?' => {
+                        let kind = kinds[digits % 3];
+                        digits /= 3;
+                        kind
+                    }
+                    c => c,
+                })
+                .collect();
+            parse_feedback(&filled)
+        })
+        .collect()
+}
+
+/// Feedback in the letter scheme of `parse_feedback`, e.g. "MPNPN"
+pub fn format_feedback(result: &MatchResult) -> String {
+    result
+        .iter()
+        .map(|kind| match kind {
+            MatchKind::Match => 'M',
+            MatchKind::Partial => 'P',
+            MatchKind::NoMatch => 'N',
+        })
+        .collect()
+}
+
+/// Feedback as a row of the squares Wordle shares, e.g. "🟩🟨⬛🟨⬛"
+pub fn format_emoji(result: &MatchResult) -> String {
+    result
+        .iter()
+        .map(|kind| match kind {
+            MatchKind::Match => '🟩',
+            MatchKind::Partial => '🟨',
+            MatchKind::NoMatch => '⬛',
+        })
+        .collect()
+}
+
+/// The feedback `guess` gets when the answer is `secret`, as a match result and its emoji
+/// row, without needing a solver. Both must be five letters, in any alphabet and case.
+pub fn evaluate(guess: &str, secret: &str) -> Result<(MatchResult, String), String> {
+    let mut letters: Vec<char> = guess
+        .chars()
+        .chain(secret.chars())
+        .map(Alphabet::normalise)
+        .collect();
+    letters.sort_unstable();
+    letters.dedup();
+    let alphabet = Alphabet::new(&letters.into_iter().collect::<String>())?;
+
+    let guess = WordEncoding::from_string(guess, &alphabet)?;
+    let secret = WordEncoding::from_string(secret, &alphabet)?;
+    let result = guess.match_result(&secret);
+    Ok((result, format_emoji(&result)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solver(words: &[&str]) -> WordleSolver {
+        let words: Vec<String> = words.iter().map(|w| w.to_string()).collect();
+        WordleSolver::from_words(
+            &words,
+            &Alphabet::english(),
+            Policy::MaximizeEntropy,
+            Vec::new(),
+        )
+        .unwrap()
+    }
+
+    // A solver over `words` spelled with A-Z and ß
+    fn german_solver(words: &[&str]) -> WordleSolver {
+        let words: Vec<String> = words.iter().map(|w| w.to_string()).collect();
+        let alphabet = Alphabet::new("ABCDEFGHIJKLMNOPQRSTUVWXYZß").unwrap();
+        WordleSolver::from_words(&words, &alphabet, Policy::MaximizeEntropy, Vec::new()).unwrap()
+    }
+
+    // Write `contents` to a file named after `name` in the temporary directory
+    fn temp_file(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("{name}.{}", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn empty_dictionary_is_rejected() {
+        let built = SolverConfig::from_words(Vec::new()).build();
+        assert!(matches!(built, Err(SolverError::Config(_))));
+    }
+
+    #[test]
+    fn single_word_dictionary_is_the_answer_at_once() {
+        let mut solver = solver(&["CRANE"]);
+        assert_eq!(solver.prior(), &[1.0]);
+
+        let guess = solver.step().unwrap();
+        assert_eq!(guess.to_string(), "CRANE");
+        assert_eq!(solver.current_guess_entropy, 0.0);
+        assert_eq!(solver.solve_probability(), 1.0);
+
+        solver.guess(|_| [MatchKind::Match; 5]).unwrap();
+        assert_eq!(solver.current_possibilities, vec![0]);
+        assert_eq!(solver.information_gained(), &[(0.0, 0.0)]);
+    }
+
+    #[test]
+    fn contradictory_feedback_is_rejected_without_changing_state() {
+        let mut solver = solver(&["CRANE", "SLATE", "TRACE"]);
+        solver.set_guess(WordEncoding::from_string("CRANE", solver.alphabet()).unwrap());
+
+        // No word in the dictionary gives five yellows against CRANE
+        let feedback = parse_feedback("PPPPP").unwrap();
+        assert_eq!(solver.guess(|_| feedback), Err(SolverError::NoCandidates));
+
+        assert_eq!(solver.current_possibilities, vec![0, 1, 2]);
+        assert!(solver.prior.iter().all(|p| p.is_finite()));
+        assert!(solver.step().is_ok());
+    }
+
+    #[test]
+    fn empty_match_results_give_a_zero_distribution() {
+        let pd = WordleSolver::compute_match_pattern_pd(&Vec::new());
+
+        assert!(pd.iter().all(|p| *p == 0.0));
+        assert_eq!(WordleSolver::compute_entropy(pd), 0.0);
+    }
+
+    #[test]
+    fn untrained_estimate_matches_its_documented_values() {
+        assert_eq!(estimate_expected_moves(0.0), 1.0);
+        assert!((estimate_expected_moves(1.0) - 1.55).abs() < 1e-12);
+        assert!((estimate_expected_moves(10.0) - 2.9).abs() < 0.01);
+    }
+
+    #[test]
+    fn interpolation_skips_degenerate_buckets() {
+        let bucket = |centre, avg_moves| Bucket { centre, avg_moves };
+        let curve = [
+            bucket(1.0, 2.0),
+            bucket(f64::NAN, 9.0),
+            bucket(3.0, 3.0),
+            bucket(3.0, 4.0),
+            bucket(5.0, f64::INFINITY),
+            bucket(5.0, 4.5),
+        ];
+
+        assert_eq!(interp_expected_moves(&curve, 0.0), 2.0);
+        assert_eq!(interp_expected_moves(&curve, 2.0), 2.75);
+        assert_eq!(interp_expected_moves(&curve, 4.0), 4.0);
+        assert_eq!(interp_expected_moves(&curve, 8.0), 4.5);
+        assert_eq!(interp_expected_moves(&curve, f64::NAN), 4.5);
+        assert!(interp_expected_moves(&[bucket(f64::NAN, 1.0)], 1.0).is_nan());
+    }
+
+    #[test]
+    fn fingerprint_matches_encodings_and_depends_on_order() {
+        let words = ["crane", "SLATE", "Trace"].map(str::to_string);
+        let solver = solver(&["CRANE", "SLATE", "TRACE"]);
+
+        assert_eq!(
+            dictionary_fingerprint(&words),
+            solver.dictionary_fingerprint()
+        );
+        // Pinned, so the value can't come to depend on the platform's word size or endianness
+        assert_eq!(dictionary_fingerprint(&words), 0x5d42_6cc5_fc64_8915);
+        let reordered = ["SLATE", "CRANE", "TRACE"].map(str::to_string);
+        assert_ne!(
+            dictionary_fingerprint(&reordered),
+            dictionary_fingerprint(&words)
+        );
+    }
+
+    #[test]
+    fn saved_state_round_trips_with_the_policy_settings() {
+        let words = ["CRANE", "SLATE", "TRACE", "CRATE"];
+        let mut solver = solver(&words);
+        solver.set_policy(Policy::MinimizeScore, Vec::new());
+        solver.set_guess_threshold(1.5);
+        solver.set_commit_threshold(0.8);
+        let word = |w: &str| WordEncoding::from_string(w, &Alphabet::english()).unwrap();
+        let feedback = word("CRANE").match_result(&word("TRACE"));
+        solver.apply(&word("CRANE"), feedback).unwrap();
+
+        let json = serde_json::to_string(&solver.save_state()).unwrap();
+        let mut restored = self::solver(&words);
+        restored
+            .load_state(serde_json::from_str(&json).unwrap())
+            .unwrap();
+        assert_eq!(restored.current_possibilities, solver.current_possibilities);
+        assert_eq!(restored.guess_threshold, 1.5);
+        assert_eq!(restored.commit_threshold, 0.8);
+
+        // A snapshot from before the thresholds were saved still loads, with the defaults
+        let mut old: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let fields = old.as_object_mut().unwrap();
+        fields.remove("guess_threshold");
+        fields.remove("commit_threshold");
+        restored
+            .load_state(serde_json::from_value(old).unwrap())
+            .unwrap();
+        assert_eq!(restored.guess_threshold, 0.0);
+        assert_eq!(restored.commit_threshold, COMMIT_THRESHOLD);
+    }
+
+    #[test]
+    fn pattern_indices_round_trip() {
+        for index in 0..243 {
+            assert_eq!(pattern_to_index(index_to_pattern(index)), index);
+        }
+        assert_eq!(pattern_to_index(parse_feedback("NNNNN").unwrap()), 0);
+        assert_eq!(pattern_to_index(parse_feedback("PNNNN").unwrap()), 1);
+        assert_eq!(pattern_to_index(parse_feedback("NNNNM").unwrap()), 162);
+        assert_eq!(
+            pattern_to_index(parse_feedback("MMMMM").unwrap()),
+            SOLVED_PATTERN as usize
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn index_to_pattern_rejects_out_of_range_indices() {
+        index_to_pattern(243);
+    }
+
+    #[test]
+    fn intersect_pools_feedback_from_both_solvers() {
+        let words = ["CRANE", "CRATE", "GRATE"];
+        let mut first = solver(&words);
+        let mut second = solver(&words);
+        let word = |w: &str| WordEncoding::from_string(w, &Alphabet::english()).unwrap();
+
+        // CRATE is the answer: CLOUD rules out only GRATE, TOWNS rules out only CRANE
+        let feedback = word("CLOUD").match_result(&word("CRATE"));
+        first.apply(&word("CLOUD"), feedback).unwrap();
+        let feedback = word("TOWNS").match_result(&word("CRATE"));
+        second.apply(&word("TOWNS"), feedback).unwrap();
+        assert_eq!(first.current_possibilities, vec![0, 1]);
+        assert_eq!(second.current_possibilities, vec![1, 2]);
+
+        first.intersect(&second).unwrap();
+        assert_eq!(first.current_possibilities, vec![1]);
+        assert_eq!(first.prior()[1], 1.0);
+
+        let other = solver(&["CRANE", "CRATE", "GRACE"]);
+        assert_eq!(
+            first.intersect(&other),
+            Err(SolverError::DictionaryMismatch)
+        );
+    }
+
+    #[test]
+    fn legacy_shards_are_upgraded_with_a_solved_column() {
+        let path = std::env::temp_dir().join(format!("legacy_shard.{}.csv", std::process::id()));
+        std::fs::write(
+            &path,
+            "secret_idx,entropy_nats,moves_remaining\n4,2.5,2\n4,0,1\n",
+        )
+        .unwrap();
+
+        assert!(upgrade_legacy_shard(&path).unwrap());
+        let upgraded = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            upgraded,
+            "secret_idx,entropy_nats,moves_remaining,solved\n4,2.5,2,1\n4,0,1,1\n"
+        );
+        assert!(!upgrade_legacy_shard(&path).unwrap());
+
+        let counts = shard_file_guess_counts(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(counts, HashMap::from([(4, Some(2))]));
+    }
+
+    #[test]
+    fn multi_board_is_solved_only_once_its_answer_is_guessed() {
+        let mut multi = MultiSolver::new(solver(&["CRANE", "SLATE", "TRACE", "CRATE"]), 2);
+        let secrets = [3, 0].map(|i| multi.boards[0].dictionary[i].clone());
+
+        let mut guesses = 0;
+        while !multi.all_solved() {
+            let guess = multi.step().unwrap();
+            multi
+                .guess(|board, guess| guess.match_result(&secrets[board]))
+                .unwrap();
+            guesses += 1;
+            for (board, secret) in secrets.iter().enumerate() {
+                assert_eq!(
+                    multi.is_solved(board),
+                    multi.previous_guesses.contains(secret)
+                );
+            }
+            assert!(guesses < 4, "last guessed {guess}");
+        }
+        assert!(secrets.iter().all(|s| multi.previous_guesses.contains(s)));
+    }
+
+    #[test]
+    fn contradictory_constraints_are_rejected_without_changing_state() {
+        let mut solver = solver(&["CRANE", "SLATE", "TRACE", "CRAZE"]);
+        let with_z = parse_constraints("+Z").unwrap();
+        solver.filter_by_constraints(&with_z).unwrap();
+        assert_eq!(solver.current_possibilities, vec![3]);
+
+        // Only CRAZE is left, so no remaining word lacks a Z
+        let without_z = parse_constraints("-Z").unwrap();
+        assert!(solver.filter_by_constraints(&without_z).is_err());
+        assert_eq!(solver.current_possibilities, vec![3]);
+        assert_eq!(solver.step().unwrap().to_string(), "CRAZE");
+    }
+
+    #[test]
+    fn frequency_keys_are_spelled_like_the_dictionary() {
+        let mut solver = german_solver(&["KLEID", "STRAß", "HAUSE"]);
+        let path = temp_file("frequencies.csv", "straß,1000\nkleid,10\n");
+
+        let found = solver.load_frequencies(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(found, 2);
+        assert!(solver.prior()[1] > solver.prior()[0]);
+    }
+
+    #[test]
+    fn weights_keys_are_spelled_like_the_dictionary() {
+        let mut solver = german_solver(&["KLEID", "STRAß", "HAUSE"]);
+        let path = temp_file("weights.csv", "Straß,5\nhause,1\n");
+
+        let found = solver.load_weights(&path, 0.5).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(found, 2);
+        assert_eq!(solver.base_weights, vec![0.5, 5.0, 1.0]);
+    }
+
+    #[test]
+    fn zipf_keys_are_spelled_like_the_dictionary() {
+        let mut solver = german_solver(&["KLEID", "STRAß", "HAUSE"]);
+        let path = temp_file("zipf.csv", "straß,5\nkleid,4\n");
+
+        let found = solver.load_zipf(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(found, 2);
+        assert_eq!(solver.base_weights[1], 1.0);
+        assert!((solver.base_weights[0] - 0.1).abs() < 1e-12);
+    }
+
+    #[test]
+    fn dictionary_sort_finds_non_ascii_words() {
+        let dictionary = temp_file("sort_words.txt", "kleid\nhause\nstraß\n");
+        let frequencies = temp_file("sort_counts.csv", "STRAß,100\nhause,5\n");
+
+        let sorted = sort_dictionary_by_frequency(&dictionary, &frequencies);
+        std::fs::remove_file(&dictionary).unwrap();
+        std::fs::remove_file(&frequencies).unwrap();
+        let (words, found) = sorted.unwrap();
+        assert_eq!(words, ["straß", "hause", "kleid"]);
+        assert_eq!(found, 2);
+    }
+
+    #[test]
+    fn step_reports_no_candidates_when_the_set_is_empty() {
+        let mut solver = solver(&["CRANE", "SLATE"]);
+        solver.current_possibilities.clear();
+        solver.update_prior();
+
+        assert!(solver.prior.iter().all(|p| *p == 0.0));
+        assert_eq!(solver.step(), Err(SolverError::NoCandidates));
+    }
+}
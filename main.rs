@@ -1,684 +1,3106 @@
-use clap::{Parser, Subcommand};
-use glob::glob;
-use std::collections::HashMap;
-use std::fs::File;
-use std::fs::OpenOptions;
-use std::io::{self, BufRead, BufWriter, Write};
-use std::path::Path;
+use clap::builder::TypedValueParser;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::f64;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use std::{f64, fmt};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use wordle_solver::{
+    Alphabet, Bucket, COMMIT_THRESHOLD, Constraints, EntropyUnit, FREQUENCY_FLOOR_WEIGHT,
+    FixedSecret, MatchResult, MultiSolver, Pattern, Policy, PrefixIndex, PriorConfig, SolverConfig,
+    SolverError, TIE_EPSILON, Verbosity, WordEncoding, WordleSolver, build_moves_histogram,
+    estimate_expected_moves, format_emoji, format_feedback, info, is_solved, merge_shards, note,
+    parse_constraints, parse_feedback, parse_uncertain_feedback, set_verbosity,
+    shard_file_guess_counts, shard_guess_counts, sort_dictionary_by_frequency,
+    upgrade_legacy_shard, verbose, verbosity,
+};
+
+// Environment variable giving the worker count when --workers is 0
+const WORKERS_ENV: &str = "WORDLE_WORKERS";
+
+/// Number of workers to run and where it came from: `requested` if non-zero, else a
+/// positive `WORDLE_WORKERS`, else one per logical CPU. It never exceeds the logical CPUs.
+fn worker_count(requested: usize) -> (usize, String) {
+    let logical = num_cpus::get();
+    if requested > 0 {
+        return (requested.min(logical), "--workers".to_string());
+    }
+    match std::env::var(WORKERS_ENV).map(|value| value.trim().parse::<usize>()) {
+        Ok(Ok(n)) if n > 0 => (n.min(logical), format!("${WORKERS_ENV}")),
+        Ok(_) => {
+            note!("⚠️  Ignoring ${WORKERS_ENV}: expected a positive number of workers");
+            (logical, "logical CPUs".to_string())
+        }
+        Err(_) => (logical, "logical CPUs".to_string()),
+    }
+}
 
-// Open 5 letter words dictionary
-fn open_dictionary<P: AsRef<Path>>(path: P) -> io::Result<Vec<String>> {
-    let file = File::open(path)?;
-    let reader = io::BufReader::new(file);
+/// Run `requested` workers (0 = see `worker_count`), either as subprocesses re-invoking
+/// this binary or, with `threads`, as threads sharing one solver's dictionary in-process.
+fn spawn_workers(
+    requested: usize,
+    kind: RunKind,
+    options: &WorkerOptions,
+    threads: bool,
+    source: &SolverSource,
+) {
+    install_interrupt_handler();
+    let (n, origin) = worker_count(requested);
+
+    if threads {
+        info!("Starting {n} {:?} worker threads (from {origin})…", kind);
+
+        // Load the dictionary once; clones share it through an `Arc`
+        let solver = load_worker_solver(source);
+        std::thread::scope(|scope| {
+            for id in 0..n {
+                let mut solver = solver.clone();
+                scope.spawn(move || {
+                    if let Err(e) = run_shard(&mut solver, kind, id, n, options, source) {
+                        eprintln!("❌  [{:?} worker {}] cannot write shard: {e}", kind, id);
+                    }
+                });
+            }
+        });
+    } else {
+        info!("Spawning {n} {:?} workers (from {origin})…", kind);
 
-    let words: Vec<String> = reader.lines().filter_map(Result::ok).collect();
+        // Encode the dictionary once up front, so the workers all load the cached encodings
+        // instead of each computing (and writing) them
+        if source.cache_dir.is_some() {
+            load_worker_solver(source);
+        }
+        let mut children = Vec::new();
+        for id in 0..n {
+            let mut cmd = Command::new(std::env::current_exe().unwrap());
+            cmd.args(source.to_args())
+                .arg(match kind {
+                    RunKind::Train => "train-worker",
+                    RunKind::Test => "test-worker",
+                })
+                .arg(id.to_string())
+                .arg(n.to_string())
+                .args(options.to_args())
+                .stdin(Stdio::null())
+                .stdout(Stdio::inherit())
+                .stderr(Stdio::inherit());
+            children.push(cmd.spawn().expect("spawn failed"));
+        }
+        // Ctrl-C reaches the whole process group, so the children stop on their own; wait
+        // for each one to finish its current secret rather than leaving it orphaned
+        for mut c in children {
+            c.wait().unwrap();
+        }
+    }
 
-    Ok(words)
+    if interrupted() {
+        note!("Interrupted – the shards hold every secret solved so far");
+        std::process::exit(130);
+    }
+    if let RunKind::Test = kind {
+        report_test_distribution(options, source);
+    }
 }
 
-#[derive(PartialEq, Clone, Copy)]
-enum MatchKind {
-    NoMatch,
-    Partial,
-    Match,
-}
+/// Print how many guesses each tested secret took, from the testing shards. A secret whose
+/// game ran out of guesses unsolved, or with no complete game in the shards (e.g. skipped
+/// by its worker), counts as a fail.
+fn report_test_distribution(options: &WorkerOptions, source: &SolverSource) {
+    let counts = match shard_guess_counts(&RunKind::Test.shards_glob(source.output_dir)) {
+        Ok(counts) => counts,
+        Err(e) => {
+            eprintln!("❌  Failed to read testing shards: {e}");
+            return;
+        }
+    };
 
-impl fmt::Display for MatchKind {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let s = match self {
-            MatchKind::NoMatch => "NoMatch",
-            MatchKind::Partial => "Partial",
-            MatchKind::Match => "Match",
-        };
-        write!(f, "{}", s)
+    let secrets = options.secrets(load_worker_solver(source).dictionary.len());
+
+    let mut distribution = GuessDistribution::new(options.max_guesses);
+    for secret_idx in secrets.clone() {
+        distribution.record(counts.get(&secret_idx).copied().flatten());
     }
+
+    println!("\nTest results over {} secrets:", secrets.len());
+    distribution.print();
 }
 
-// Declare a custom match result
-type MatchResult = [MatchKind; 5];
+// Default --lambda: one expected guess is worth a bit of entropy
+const DEFAULT_LAMBDA: f64 = 1.0;
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-struct WordEncoding {
-    positions: [char; 5],  // Encode symbol position
-    frequencies: [u8; 26], // Encode symbol frequency
-}
+// Entropy bucket width (bits) of the expected-moves histogram
+const DEFAULT_BUCKET_WIDTH: f64 = 0.20;
 
-impl WordEncoding {
-    /// helper: A→0, B→1, … Z→25
-    #[inline]
-    fn idx(c: char) -> usize {
-        (c.to_ascii_uppercase() as u8 - b'A') as usize
-    }
+/// Build the expected-moves curve from the training shards under `output_dir`, if any
+/// exist and parse
+fn load_expected_moves_curve(bucket_width: f64, output_dir: &Path) -> Option<Vec<Bucket>> {
+    let shards_glob = &RunKind::Train.shards_glob(output_dir);
 
-    pub fn to_string(&self) -> String {
-        self.positions.iter().collect()
+    if !glob::glob(shards_glob)
+        .expect("bad glob pattern")
+        .any(|res| res.as_ref().map(|p| p.is_file()).unwrap_or(false))
+    {
+        return None; // no training data yet
     }
 
-    pub fn from_string(word: &str) -> WordEncoding {
-        let mut positions = ['A'; 5];
-        let mut frequencies = [0u8; 26];
-
-        for (i, c) in word.chars().enumerate() {
-            let cu = c.to_ascii_uppercase();
-            positions[i] = cu;
-            frequencies[Self::idx(cu)] += 1;
+    // at least one shard exists → build histogram
+    match build_moves_histogram(shards_glob, bucket_width) {
+        Ok(buckets) if !buckets.is_empty() => {
+            info!("Loaded expected‑moves curve from training data ✅");
+            Some(buckets)
         }
-
-        WordEncoding {
-            positions,
-            frequencies,
+        Ok(_) => {
+            note!("⚠️  Training data present but contains no usable rows – using entropy policy");
+            None
+        }
+        Err(e) => {
+            note!(
+                "⚠️  Training data present but histogram build failed ({e}) – using entropy policy"
+            );
+            None
         }
     }
+}
 
-    pub fn match_result(&self, other: &WordEncoding) -> MatchResult {
-        let mut result = [MatchKind::NoMatch; 5];
-        let mut remaining = other.frequencies; // local mutable copy
-
-        for i in 0..5 {
-            if self.positions[i] == other.positions[i] {
-                result[i] = MatchKind::Match;
-                remaining[Self::idx(self.positions[i])] -= 1;
-            }
+/// Write the expected-moves curve built from the training shards as `centre,avg_moves` CSV,
+/// to `out` or stdout. Centres are in `unit`, named in the header unless they are bits
+/// (e.g. `centre_nats`); `bucket_width` is always in bits, like the curve itself.
+fn export_histogram(bucket_width: f64, out: Option<&Path>, source: &SolverSource) {
+    let unit = source.entropy_unit;
+    let shards_glob = RunKind::Train.shards_glob(source.output_dir);
+    let buckets = match build_moves_histogram(&shards_glob, bucket_width) {
+        Ok(buckets) if !buckets.is_empty() => buckets,
+        Ok(_) => {
+            eprintln!("❌  No training data matching {}", shards_glob);
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("❌  Failed to build histogram: {e}");
+            std::process::exit(1);
         }
+    };
 
-        for i in 0..5 {
-            if result[i] == MatchKind::NoMatch {
-                let idx = Self::idx(self.positions[i]);
-                if remaining[idx] > 0 {
-                    result[i] = MatchKind::Partial;
-                    remaining[idx] -= 1;
-                }
+    let mut writer: Box<dyn Write> = match out {
+        Some(path) => match File::create(path) {
+            Ok(file) => Box::new(BufWriter::new(file)),
+            Err(e) => {
+                eprintln!("❌  Cannot create {}: {e}", path.display());
+                std::process::exit(1);
             }
-        }
+        },
+        None => Box::new(io::stdout().lock()),
+    };
 
-        result
+    let centre = match unit {
+        EntropyUnit::Bits => "centre".to_string(),
+        unit => format!("centre_{unit}"),
+    };
+    let result = writeln!(writer, "{centre},avg_moves").and_then(|_| {
+        for bucket in &buckets {
+            writeln!(
+                writer,
+                "{},{}",
+                unit.from_bits(bucket.centre),
+                bucket.avg_moves
+            )?;
+        }
+        writer.flush()
+    });
+    if let Err(e) = result {
+        eprintln!("❌  Failed to write histogram: {e}");
+        std::process::exit(1);
     }
 }
 
-#[derive(PartialEq)]
-enum Policy {
-    MaximizeEntropy,
-    MinimizeScore,
-}
-
-struct WordleSolver {
-    dictionary: Vec<WordEncoding>, // Dictionary as tuple of WordEncoding, sorted by rank. E.G. dictionary[0] is the word with the highest frequency
-    policy: Policy,                // The policy of the algorithm
-    expected_moves_curve: Vec<Bucket>, // The expected moves given an entropy (from our training)
-    previous_guesses: Vec<WordEncoding>, // Track previous guesses
-
-    // These are our state variables - should be updated on every iteration or guess
-    prior: Vec<f64>, // P_W(w): The probability mass function of how plausible our word is the answer
-    current_possibilities: Vec<usize>, // Set of current possibilities (W), stored as indices of elements in dictionary.
+/// Concatenate the `kind` shards under `output_dir` into one file, leaving out malformed
+/// rows. With `strict`, any malformed row is an error and no merged file is left behind.
+fn merge_shard_files(kind: RunKind, output_dir: &Path, out: &Path, strict: bool) {
+    let shards_glob = kind.shards_glob(output_dir);
+    let summary = match merge_shards(&shards_glob, out) {
+        Ok(summary) => summary,
+        Err(e) => {
+            eprintln!("❌  Failed to merge shards: {e}");
+            std::process::exit(1);
+        }
+    };
 
-    // These are values derived from our state
-    current_guess: Option<WordEncoding>,
-    current_guess_entropy: f64,
-    current_guess_match_result: Option<Vec<(MatchResult, f64)>>,
-    current_guess_match_pattern_pd: Option<[f64; 243]>,
-    current_expected_score: f64,
+    if summary.shards == 0 {
+        let _ = std::fs::remove_file(out);
+        eprintln!("❌  No shards matching {}", shards_glob);
+        std::process::exit(1);
+    }
+    if strict && summary.malformed > 0 {
+        let _ = std::fs::remove_file(out);
+        eprintln!(
+            "❌  {} malformed row(s) in the shards; nothing written",
+            summary.malformed
+        );
+        std::process::exit(1);
+    }
+    println!(
+        "Merged {} rows from {} shard(s) into {}",
+        summary.rows,
+        summary.shards,
+        out.display()
+    );
 }
 
-impl WordleSolver {
-    pub fn intialise(
-        dictionary_path: &String,
-        policy: Policy,
-        expected_moves_curve: Vec<Bucket>,
-    ) -> Result<WordleSolver, String> {
-        let mut solver: WordleSolver;
-
-        let dictionary_result = open_dictionary(dictionary_path);
-        if dictionary_result.is_err() {
-            return Err(format!(
-                "Error opening dictionary: {}",
-                dictionary_result.unwrap_err()
-            )
-            .to_string());
+/// Write `dictionary` to `out`, one word per line, most common first by `freq_file`
+fn sort_dictionary_file(dictionary: &Path, freq_file: &Path, out: &Path) {
+    let (words, found) = match sort_dictionary_by_frequency(dictionary, freq_file) {
+        Ok(sorted) => sorted,
+        Err(e) => {
+            eprintln!("❌  Failed to sort {}: {e}", dictionary.display());
+            std::process::exit(1);
         }
+    };
 
-        let dictionary = dictionary_result.unwrap();
-        let dictionary_len = dictionary.len();
-
-        println!("Loaded dictionary with {} words", dictionary_len);
-
-        solver = WordleSolver {
-            dictionary: WordleSolver::compute_word_encodings(&dictionary),
-            policy,
-            previous_guesses: Vec::new(),
-            prior: vec![0.0; dictionary_len],
-            current_possibilities: (0..dictionary_len).collect(),
-            current_guess: None,
-            current_guess_entropy: 0.0,
-            current_guess_match_result: None,
-            current_guess_match_pattern_pd: None,
-            current_expected_score: f64::INFINITY,
-            expected_moves_curve: expected_moves_curve,
-        };
+    let result = File::create(out).and_then(|file| {
+        let mut writer = BufWriter::new(file);
+        for word in &words {
+            writeln!(writer, "{}", word)?;
+        }
+        writer.flush()
+    });
+    if let Err(e) = result {
+        eprintln!("❌  Failed to write {}: {e}", out.display());
+        std::process::exit(1);
+    }
+    println!(
+        "Wrote {} words to {} ({} ranked by frequency, {} without a count at the end)",
+        words.len(),
+        out.display(),
+        found,
+        words.len() - found
+    );
+}
 
-        //  Update the prior in the solver before returning it
-        solver.update_prior();
+/// Use data‑driven scoring when a training curve exists, otherwise maximise entropy
+fn detect_policy(bucket_width: f64, output_dir: &Path) -> (Policy, Vec<Bucket>) {
+    match load_expected_moves_curve(bucket_width, output_dir) {
+        Some(curve) => (Policy::MinimizeScore, curve),
+        None => (Policy::MaximizeEntropy, Vec::new()),
+    }
+}
 
-        Ok(solver)
+/// The policy requested on the command line, or the detected one when none was. Scoring
+/// (alone or blended with entropy by the source's `lambda`) reads the training curve from
+/// the source's output directory, and without one estimates the expected moves in closed
+/// form.
+fn resolve_policy(
+    policy: Option<PolicyArg>,
+    source: &SolverSource,
+    bucket_width: f64,
+) -> (Policy, Vec<Bucket>) {
+    let scoring = match policy {
+        None => return detect_policy(bucket_width, source.output_dir),
+        Some(PolicyArg::Entropy) => return (Policy::MaximizeEntropy, Vec::new()),
+        Some(PolicyArg::WorstCase) => return (Policy::MinimizeWorstCase, Vec::new()),
+        Some(PolicyArg::Score) => Policy::MinimizeScore,
+        Some(PolicyArg::Blend) => Policy::Blend {
+            lambda: source.lambda,
+        },
+    };
+    match load_expected_moves_curve(bucket_width, source.output_dir) {
+        Some(curve) => (scoring, curve),
+        None => {
+            note!("⚠️  No training curve for the score policy – using the untrained estimate");
+            (scoring, Vec::new())
+        }
     }
+}
 
-    pub fn reset(&mut self) {
-        // Reset values
-        self.current_guess = None;
-        self.current_guess_entropy = 0.0;
-        self.current_guess_match_result = None;
-        self.current_guess_match_pattern_pd = None;
-        self.current_expected_score = f64::INFINITY;
-        self.previous_guesses.clear();
+fn interactive_play(source: &SolverSource, options: &PlayOptions, start: Option<&str>) {
+    // ------------------------------------------------------------ //
+    // 1.  Create solver with the requested policy, or data‑driven  //
+    //     scoring when a training curve exists, and --green        //
+    // ------------------------------------------------------------ //
+    let mut solver = load_play_solver(source, options); // <‑‑ curve may be empty
+    apply_start_word(&mut solver, start);
+    print_expected_guesses(&solver);
+
+    let mut log = TranscriptLog::open(options.log.as_deref());
+    let mut timer = StepTimer::new(source.timing);
+    play_loop(&mut solver, options, &mut log, &mut timer);
+    timer.report("play");
+}
 
-        // Reset possibilties
-        self.current_possibilities = (0..self.dictionary.len()).collect();
+/// Let the solver play against `secret`, generating the feedback itself, and print each
+/// turn: the guess, its feedback, the expected information and the words left
+fn auto_play(source: &SolverSource, options: &PlayOptions, secret: &str, start: Option<&str>) {
+    let mut solver = load_play_solver(source, options);
+    apply_start_word(&mut solver, start);
 
-        // Reset prior
-        self.update_prior();
+    let secret = match WordEncoding::from_string(secret, solver.alphabet()) {
+        Ok(word) if solver.dictionary.contains(&word) => word,
+        Ok(word) => {
+            eprintln!("❌  Secret '{}' is not in the dictionary", word);
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("❌  Invalid secret: {e}");
+            std::process::exit(1);
+        }
+    };
+    if !solver
+        .current_possibilities
+        .iter()
+        .any(|i| solver.dictionary[*i] == secret)
+    {
+        eprintln!("❌  Secret '{}' doesn't match the --green letters", secret);
+        std::process::exit(1);
     }
 
-    // Update our prior with the current possibilities
-    pub fn update_prior(&mut self) {
-        let mut weights = vec![0.0; self.dictionary.len()];
-        let parametric_sigmoid = |x: f64, midpoint: f64, steepness: f64| -> f64 {
-            1.0 / (1.0 + (steepness * (x - midpoint)).exp())
+    let mut log = TranscriptLog::open(options.log.as_deref());
+    let mut timer = StepTimer::new(source.timing);
+    let unit = solver.entropy_unit();
+    for turn in 1.. {
+        print_candidates(&solver, options);
+        let remaining = solver.current_possibilities.len();
+        let guess = match timer.time(|| solver.step()) {
+            Ok(guess) => guess,
+            Err(e) => {
+                eprintln!("failed to find solution: {e}");
+                std::process::exit(1);
+            }
         };
+        let feedback = guess.match_result(&secret);
+        println!(
+            "{}. {}  {} {}  (expected ΔEntropy {:.3} {unit}, {} possibilities)",
+            turn,
+            guess,
+            format_emoji(&feedback),
+            format_feedback(&feedback),
+            solver.in_entropy_unit(solver.current_guess_entropy),
+            remaining
+        );
 
-        let mut sum_weight: f64 = 0.0;
-        for w in self.current_possibilities.iter() {
-            weights[*w] = parametric_sigmoid(*w as f64, 1500.0, 0.05);
-            sum_weight += weights[*w]
+        if is_solved(&feedback) {
+            log.record(&guess, &[feedback], 1);
+            println!("Solved {} in {} guesses", secret, turn);
+            break;
         }
-
-        // Update the prior probabilities
-        for i in 0..self.prior.len() {
-            self.prior[i] = weights[i] / sum_weight;
+        if let Err(e) = solver.guess(|_| feedback) {
+            eprintln!("failed to find solution: {e}");
+            std::process::exit(1);
         }
+        log.record(&guess, &[feedback], solver.current_possibilities.len());
     }
+    timer.report("auto");
+}
 
-    pub fn guess<CheckFunction>(&mut self, callback: CheckFunction)
-    where
-        CheckFunction: Fn(&WordEncoding) -> MatchResult,
-    {
-        if let Some(some_guess) = &self.current_guess {
-            self.previous_guesses.push(*some_guess);
-
-            let actual_match = callback(some_guess);
-
-            let keep_indices: Vec<usize> = self
-                .current_guess_match_result
-                .as_ref()
-                .unwrap()
-                .iter()
-                .enumerate()
-                .filter(|(_, val)| (**val).0 == actual_match)
-                .map(|(index, _)| index)
-                .collect();
+/// Replay a transcript of `word:pattern` guesses, then continue with the interactive loop.
+/// With no transcript on the command line it is read as one line from stdin.
+fn resume_play(source: &SolverSource, options: &PlayOptions, transcript: &[String]) {
+    let mut solver = load_play_solver(source, options);
 
-            self.current_possibilities = keep_indices
-                .iter()
-                .map(|i| self.current_possibilities[*i])
-                .collect();
+    let transcript = if transcript.is_empty() {
+        let Some(line) = prompt("Enter the guesses so far (e.g. CRANE:NPNPM RAISE:NMNNM): ") else {
+            return; // EOF
+        };
+        line.split_whitespace().map(str::to_string).collect()
+    } else {
+        transcript.to_vec()
+    };
 
-            self.update_prior();
-        }
+    let mut log = TranscriptLog::open(options.log.as_deref());
+    if let Some(word) = replay_transcript(&mut solver, &transcript, true, &mut log) {
+        println!("Solution Found: {}", word);
+        return;
     }
 
-    pub fn step(&mut self) {
-        self.current_guess = None;
-        self.current_guess_entropy = 0.0;
-        self.current_guess_match_result = None;
-        self.current_guess_match_pattern_pd = None;
-        self.current_expected_score = f64::INFINITY;
-
-        // Calculate entropy of every possibilities
-        for (i, guess) in self.dictionary.iter().enumerate() {
-            // Do not repeat our guess
-            if self.previous_guesses.contains(guess) {
-                continue;
-            }
-
-            let mut match_results: Vec<(MatchResult, f64)> = Vec::new();
-
-            for j in self.current_possibilities.iter() {
-                let match_pattern = guess.match_result(&self.dictionary[*j]);
-                match_results.push((match_pattern, self.prior[*j]))
-            }
+    let mut timer = StepTimer::new(source.timing);
+    play_loop(&mut solver, options, &mut log, &mut timer);
+    timer.report("resume");
+}
 
-            let match_pattern_pd = WordleSolver::compute_match_pattern_pd(&match_results);
-            let entropy = WordleSolver::compute_entropy(match_pattern_pd);
+/// Transcript of a game being played, appended to the `--log` file if there is one. Each
+/// guess is a line of `word:pattern:remaining`, flushed straight away, so the file can be
+/// passed to `resume` (e.g. `resume $(cat game.log)`) even after a crash. Constraints
+/// entered at the prompt are not recorded.
+struct TranscriptLog {
+    file: Option<(File, PathBuf)>,
+}
 
-            if self.policy == Policy::MaximizeEntropy {
-                if entropy > self.current_guess_entropy {
-                    self.current_guess = Some(*guess);
-                    self.current_guess_entropy = entropy;
-                    self.current_guess_match_result = Some(match_results);
-                    self.current_guess_match_pattern_pd = Some(match_pattern_pd);
-                }
-            } else if self.policy == Policy::MinimizeScore {
-                // We really need to punish when the prior is zero - we only want to explore when prior is zero
-
-                let expected_score = 1.0
-                    + (1.0 - self.prior[i])
-                        * self.compute_expected_score(
-                            (self.current_possibilities.len() as f64).log2() - entropy,
-                        );
-
-                if expected_score < self.current_expected_score {
-                    self.current_guess = Some(*guess);
-                    self.current_guess_entropy = entropy;
-                    self.current_guess_match_result = Some(match_results);
-                    self.current_guess_match_pattern_pd = Some(match_pattern_pd);
-                    self.current_expected_score = expected_score;
+impl TranscriptLog {
+    /// Open `path` for appending, exiting with a message if it can't be; no path, no log
+    fn open(path: Option<&Path>) -> TranscriptLog {
+        let file = path.map(
+            |path| match OpenOptions::new().create(true).append(true).open(path) {
+                Ok(file) => (file, path.to_path_buf()),
+                Err(e) => {
+                    eprintln!("❌  Cannot open log {}: {e}", path.display());
+                    std::process::exit(1);
                 }
-            }
-        }
+            },
+        );
+        TranscriptLog { file }
     }
 
-    fn compute_word_encodings(words: &Vec<String>) -> Vec<WordEncoding> {
-        let mut encodings: Vec<WordEncoding> = Vec::new();
-
-        // Compute encoding for each word
-        for word in words {
-            let encoding = WordEncoding::from_string(word);
-            encodings.push(encoding);
+    /// Record that `guess` got `feedback`, leaving `remaining` possibilities. A failed write
+    /// is reported once and stops the logging; the game goes on.
+    fn record(&mut self, guess: &WordEncoding, feedback: &[MatchResult], remaining: usize) {
+        let Some((file, path)) = &mut self.file else {
+            return;
+        };
+        let line = format!(
+            "{}:{}:{}\n",
+            guess,
+            format_uncertain_feedback(feedback),
+            remaining
+        );
+        if let Err(e) = file.write_all(line.as_bytes()).and_then(|_| file.flush()) {
+            note!("⚠️  Stopped logging to {}: {e}", path.display());
+            self.file = None;
         }
-
-        encodings
     }
+}
 
-    // Compute the 'match pattern' probability distribution (pd), of a given word over the possibility
-    fn compute_match_pattern_pd(match_results: &Vec<(MatchResult, f64)>) -> [f64; 243] {
-        let mut sum: f64 = 0.0;
-        let mut match_pattern_pd: [f64; 243] = [0.0; 243];
-
-        for (match_result, likelihood) in match_results {
-            // Compute the index
-            let mut index: usize = 0;
-
-            for i in 0..5 {
-                match match_result[i] {
-                    MatchKind::NoMatch => index += 0 * (3 as usize).pow(i as u32),
-                    MatchKind::Partial => index += 1 * (3 as usize).pow(i as u32),
-                    MatchKind::Match => index += 2 * (3 as usize).pow(i as u32),
-                }
+/// Apply each `word:pattern` entry as an explicit guess, exiting on an invalid entry.
+/// Returns the word if one of the patterns was all green.
+fn replay_transcript(
+    solver: &mut WordleSolver,
+    transcript: &[String],
+    verbose: bool,
+    log: &mut TranscriptLog,
+) -> Option<WordEncoding> {
+    for entry in transcript {
+        // A logged entry also records how many possibilities it left
+        let (played, logged_remaining) = match entry.rsplit_once(':') {
+            Some((played, count)) if played.contains(':') => match count.parse::<usize>() {
+                Ok(count) => (played, Some(count)),
+                Err(_) => (entry.as_str(), None),
+            },
+            _ => (entry.as_str(), None),
+        };
+        let (word, feedback) = match parse_played_word(played, solver.alphabet()) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                eprintln!("❌  {e}");
+                std::process::exit(1);
             }
-
-            match_pattern_pd[index] += likelihood;
-            sum += likelihood;
+        };
+        if !solver.dictionary.contains(&word) {
+            eprintln!("❌  '{}' is not in the dictionary", word);
+            std::process::exit(1);
         }
 
-        // Normalise
-        for x in match_pattern_pd.iter_mut() {
-            *x /= sum;
+        solver.set_guess(word.clone());
+        if solver.guess_any_of(&feedback).is_err() {
+            eprintln!("❌  Contradictory feedback at {}: no words match", entry);
+            std::process::exit(1);
+        }
+        let remaining = solver.current_possibilities.len();
+        if let Some(logged) = logged_remaining
+            && logged != remaining
+        {
+            eprintln!(
+                "⚠️  {} left {} possibilities when logged but {} now (different dictionary, prior or constraints?)",
+                played.to_uppercase(),
+                logged,
+                remaining
+            );
+        }
+        log.record(&word, &feedback, remaining);
+        if verbose {
+            println!(
+                "Replayed {}: {} possibilities remain",
+                entry.to_uppercase(),
+                solver.current_possibilities.len()
+            );
         }
 
-        match_pattern_pd
+        if is_solved_by(&feedback) {
+            return Some(word);
+        }
     }
+    None
+}
 
-    fn compute_entropy<const N: usize>(pd: [f64; N]) -> f64 {
-        let mut entropy: f64 = 0.0;
-        for probabilty in pd.iter() {
-            if *probabilty > 0.0 {
-                entropy += -1.0 * (*probabilty) * (*probabilty).log2();
+/// Print every word still consistent with `transcript` and `constraints`, most likely first
+fn filter_words(
+    source: &SolverSource,
+    transcript: &[String],
+    constraints: Option<&str>,
+    top: Option<usize>,
+) {
+    let mut solver = load_solver(source, Policy::MaximizeEntropy, Vec::new());
+
+    if let Some(word) = replay_transcript(
+        &mut solver,
+        transcript,
+        false,
+        &mut TranscriptLog::open(None),
+    ) {
+        println!("{}", word);
+        return;
+    }
+    if let Some(spec) = constraints {
+        match parse_constraints(spec)
+            .and_then(|constraints| solver.filter_by_constraints(&constraints))
+        {
+            Ok(()) => {}
+            Err(e) => {
+                eprintln!("❌  {e}");
+                std::process::exit(1);
             }
         }
-
-        entropy
     }
 
-    fn compute_expected_score(&self, entropy: f64) -> f64 {
-        if self.expected_moves_curve.is_empty() {
-            // fallback: rough proxy = entropy itself
-            entropy
-        } else {
-            interp_expected_moves(&self.expected_moves_curve, entropy)
+    list_candidates(&solver, top);
+}
+
+/// Print the dictionary words containing `letters` in any position and none of `excluded`,
+/// most common first
+fn print_words_containing(
+    source: &SolverSource,
+    letters: &str,
+    excluded: &str,
+    top: Option<usize>,
+) {
+    let solver = load_solver(source, Policy::MaximizeEntropy, Vec::new());
+    let words = match solver.words_containing(letters, excluded) {
+        Ok(words) => words,
+        Err(e) => {
+            eprintln!("❌  {e}");
+            std::process::exit(1);
         }
+    };
+
+    let shown = top.unwrap_or(words.len()).min(words.len());
+    for i in &words[..shown] {
+        println!("{}", solver.dictionary[*i]);
+    }
+    if shown < words.len() {
+        println!("... and {} more", words.len() - shown);
     }
+    info!("{} words contain {}", words.len(), letters.to_uppercase());
 }
 
-#[derive(Clone, Copy, Debug)]
-struct Bucket {
-    centre: f64,    // bucket midpoint (x axis)
-    avg_moves: f64, // average moves‑remaining in this bucket
+/// Print the remaining words, most likely first, optionally only the `top` most likely
+fn list_candidates(solver: &WordleSolver, top: Option<usize>) {
+    let candidates = solver.candidate_probabilities();
+    let shown = top.unwrap_or(candidates.len()).min(candidates.len());
+    for (word, _) in &candidates[..shown] {
+        println!("{}", word);
+    }
+    if shown < candidates.len() {
+        println!("... and {} more", candidates.len() - shown);
+    }
 }
 
-fn build_moves_histogram(glob_pattern: &str, bucket_width: f64) -> io::Result<Vec<Bucket>> {
-    let mut sum_moves: HashMap<i64, f64> = HashMap::new();
-    let mut counts: HashMap<i64, usize> = HashMap::new();
+/// The arguments of `line` if its first word is the prompt command `name`
+fn strip_command<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    let (command, args) = line.split_once(' ').unwrap_or((line, ""));
+    (command == name).then_some(args)
+}
 
-    for entry in glob(glob_pattern).map_err(|e| io::Error::new(io::ErrorKind::Other, e))? {
-        let path = entry.map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+/// Handle `list [N]` typed at a prompt
+fn handle_list(solver: &WordleSolver, args: &str) {
+    match args.trim() {
+        "" => list_candidates(solver, None),
+        n => match n.parse::<usize>() {
+            Ok(n) => list_candidates(solver, Some(n)),
+            Err(_) => eprintln!("Expected `list` or `list <count>`, got: list {}", n),
+        },
+    }
+}
 
-        let file = std::fs::File::open(&path)?; // ordinary io::Error
-        for line in io::BufReader::new(file).lines().skip(1) {
-            let l = line?;
-            let mut it = l.split(',');
-            it.next(); // secret_idx
-            let entropy: f64 = it.next().unwrap().parse().unwrap();
-            let moves: f64 = it.next().unwrap().parse().unwrap();
+/// Print how many remaining possibilities have each letter at each position, for the
+/// letters that still appear anywhere. `·` marks a letter ruled out at that position.
+fn print_heatmap(solver: &WordleSolver) {
+    let counts = solver.position_counts();
 
-            let idx = (entropy / bucket_width).floor() as i64;
-            *sum_moves.entry(idx).or_insert(0.0) += moves;
-            *counts.entry(idx).or_insert(0) += 1;
+    println!(
+        "Letters by position over {} possibilities:",
+        solver.current_possibilities.len()
+    );
+    println!("  {:>6} {:>6} {:>6} {:>6} {:>6}", 1, 2, 3, 4, 5);
+    for (letter_idx, letter) in solver.alphabet().letters().iter().enumerate() {
+        if counts.iter().all(|position| position[letter_idx] == 0) {
+            continue;
+        }
+        print!("{}", letter);
+        for position in &counts {
+            match position[letter_idx] {
+                0 => print!(" {:>6}", "·"),
+                count => print!(" {:>6}", count),
+            }
         }
+        println!();
     }
+}
 
-    let mut buckets: Vec<Bucket> = sum_moves
+/// Warn about repeated letters in `guess` (e.g. "note: double L"), whose feedback is easy to
+/// misread: a second copy of a letter is only yellow if the answer has it twice
+fn print_duplicate_hint(solver: &WordleSolver, guess: &WordEncoding, options: &PlayOptions) {
+    if options.no_duplicate_hints {
+        return;
+    }
+    let repeated: Vec<String> = guess
+        .repeated_letters(solver.alphabet())
         .into_iter()
-        .map(|(idx, sum)| {
-            let count = counts[&idx];
-            Bucket {
-                centre: (idx as f64 + 0.5) * bucket_width,
-                avg_moves: sum / count as f64,
-            }
+        .map(|(c, count)| match count {
+            2 => format!("double {c}"),
+            3 => format!("triple {c}"),
+            n => format!("{n}× {c}"),
         })
         .collect();
+    if !repeated.is_empty() {
+        println!(
+            "note: {} – enter the feedback for each copy carefully",
+            repeated.join(" and ")
+        );
+    }
+}
 
-    buckets.sort_by(|a, b| a.centre.partial_cmp(&b.centre).unwrap());
-    Ok(buckets)
+/// Print how many guesses the solver expects to need from its current state, from the
+/// training curve or, without one, the untrained estimate
+fn print_expected_guesses(solver: &WordleSolver) {
+    match solver.expected_guesses() {
+        Some(guesses) => println!("Expected guesses from start: ~{:.1}", guesses),
+        None => println!(
+            "Expected guesses from start: ~{:.1} (untrained estimate)",
+            estimate_expected_moves((solver.remaining_count() as f64).log2())
+        ),
+    }
 }
 
-/// Linear interpolation (flat extrapolation) on the buckets.
-fn interp_expected_moves(buckets: &[Bucket], entropy: f64) -> f64 {
-    match buckets {
-        [] => f64::NAN,
-        [only] => only.avg_moves,
-        _ => {
-            if entropy <= buckets[0].centre {
-                return buckets[0].avg_moves;
-            }
-            if entropy >= buckets.last().unwrap().centre {
-                return buckets.last().unwrap().avg_moves;
-            }
-            for w in buckets.windows(2) {
-                let (l, r) = (w[0], w[1]);
-                if entropy >= l.centre && entropy <= r.centre {
-                    let t = (entropy - l.centre) / (r.centre - l.centre);
-                    return l.avg_moves + t * (r.avg_moves - l.avg_moves);
-                }
-            }
-            unreachable!()
-        }
+/// Print the chance the suggested guess wins now and, given a training curve, the chance of
+/// winning within the guesses left
+fn print_solve_odds(solver: &WordleSolver) {
+    let now = format!(
+        "P(solve this turn): {:.1}%",
+        100.0 * solver.solve_probability()
+    );
+    match solver.win_probability() {
+        Some(p) => println!(
+            "{now}, P(win within {} guesses): {:.1}%",
+            solver.guesses_left(),
+            100.0 * p
+        ),
+        None => println!("{now}"),
     }
 }
 
-fn spawn_workers(requested: usize, kind: RunKind) {
-    let logical = num_cpus::get();
-    let n = if requested == 0 {
-        logical
-    } else {
-        requested.min(logical)
+/// Handle `dist <word>` typed at a prompt: print the feedback `word` could get against the
+/// remaining possibilities, most likely first
+fn print_pattern_distribution(solver: &WordleSolver, args: &str) {
+    let guess = match WordEncoding::from_string(args.trim(), solver.alphabet()) {
+        Ok(guess) => guess,
+        Err(e) => {
+            eprintln!("Expected `dist <word>`: {e}");
+            return;
+        }
     };
 
-    println!("Spawning {n} {:?} workers…", kind);
-
-    let mut children = Vec::new();
-    for id in 0..n {
-        let mut cmd = Command::new(std::env::current_exe().unwrap());
-        cmd.arg(match kind {
-            RunKind::Train => "train-worker",
-            RunKind::Test => "test-worker",
-        })
-        .arg(id.to_string())
-        .arg(n.to_string())
-        .stdin(Stdio::null())
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit());
-        children.push(cmd.spawn().expect("spawn failed"));
-    }
-    for mut c in children {
-        c.wait().unwrap();
+    let distribution = solver.pattern_distribution(&guess);
+    println!(
+        "{} against {} possibilities gives {} patterns:",
+        guess,
+        solver.current_possibilities.len(),
+        distribution.len()
+    );
+    for (pattern, probability, words) in distribution {
+        println!(
+            "  {} {}  {:>6.2}%  {} word{}",
+            format_emoji(&pattern),
+            format_feedback(&pattern),
+            100.0 * probability,
+            words,
+            if words == 1 { "" } else { "s" }
+        );
     }
 }
 
-fn interactive_play() {
-    let shards_glob = "./train/training_data*.csv";
-    let mut curve = Vec::new();
-
-    let policy: Policy;
+/// Handle `risk` typed at a prompt: print what each feedback to the current guess would
+/// leave and how much it would tell
+fn print_risk_map(solver: &WordleSolver) {
+    let (Some(guess), Some(map)) = (&solver.current_guess, solver.risk_map()) else {
+        println!("No guess to map yet");
+        return;
+    };
 
-    if glob::glob(shards_glob)
-        .expect("bad glob pattern")
-        .any(|res| res.as_ref().map(|p| p.is_file()).unwrap_or(false))
-    {
-        // at least one shard exists → build histogram & switch policy
-        match build_moves_histogram(shards_glob, 0.20) {
-            Ok(buckets) if !buckets.is_empty() => {
-                curve = buckets;
-                policy = Policy::MinimizeScore; // use data‑driven scoring
-                println!("Loaded expected‑moves curve from training data ✅");
-            }
-            _ => {
-                eprintln!(
-                    "⚠️  Training data present but histogram build failed – using entropy policy"
-                );
-                policy = Policy::MaximizeEntropy;
-            }
-        }
-    } else {
-        policy = Policy::MaximizeEntropy; // no training data yet
+    println!("Feedback {} can get:", guess);
+    for (pattern, words, probability, entropy) in map {
+        println!(
+            "  {} {}  {:>6.2}%  leaves {} word{}, {:.2} {} left, {:.2} {} learned",
+            format_emoji(&pattern),
+            format_feedback(&pattern),
+            100.0 * probability,
+            words,
+            if words == 1 { "" } else { "s" },
+            solver.in_entropy_unit(entropy),
+            solver.entropy_unit(),
+            solver.in_entropy_unit(-probability.log2()),
+            solver.entropy_unit()
+        );
     }
+}
 
-    // ------------------------------------------------------------ //
-    // 2.  Create solver with chosen policy & curve                 //
-    // ------------------------------------------------------------ //
-    let mut solver = match WordleSolver::intialise(
-        &"./words_5_letters.txt".to_string(),
-        policy,
-        curve, // <‑‑ pass curve (may be empty)
-    ) {
-        Ok(s) => s,
-        Err(e) => {
-            eprintln!("failed to initialize WordleSolver: {e}");
-            std::process::exit(1);
-        }
+/// Print why the solver suggested its current guess
+fn print_explanation(solver: &WordleSolver) {
+    let Some(explanation) = solver.explain() else {
+        println!("No guess to explain yet");
+        return;
     };
 
+    println!(
+        "{} splits {} possibilities into {} feedback patterns; the largest leaves {}",
+        explanation.guess,
+        solver.current_possibilities.len(),
+        explanation.patterns,
+        explanation.largest_bucket
+    );
+    println!(
+        "Chance {} is the answer: {:.1}%",
+        explanation.guess,
+        explanation.answer_probability * 100.0
+    );
+    let unit = solver.entropy_unit();
+    match explanation.runner_up {
+        Some((word, entropy)) => println!(
+            "Expected ΔEntropy: {:.3} {unit}, vs {:.3} for the runner-up {}",
+            solver.in_entropy_unit(explanation.entropy),
+            solver.in_entropy_unit(entropy),
+            word
+        ),
+        None => println!(
+            "Expected ΔEntropy: {:.3} {unit}",
+            solver.in_entropy_unit(explanation.entropy)
+        ),
+    }
+}
+
+/// Suggest guesses and read feedback until a single possibility remains
+fn play_loop(
+    solver: &mut WordleSolver,
+    options: &PlayOptions,
+    log: &mut TranscriptLog,
+    timer: &mut StepTimer,
+) {
+    if announce_only_word(solver) {
+        return;
+    }
     while solver.current_possibilities.len() > 1 {
         let initial_possibilities = solver.current_possibilities.len();
+        print_candidates(solver, options);
 
         // Prime the initial guess using step()
-        solver.step();
-
-        if solver.current_guess.is_none() {
-            eprintln!("failed to find solution: cannot generate next guess");
-            std::process::exit(1);
-        }
-
-        let guess = solver.current_guess.as_ref().unwrap();
+        let guess = match timer.time(|| solver.step()) {
+            Ok(guess) => guess,
+            Err(e) => {
+                eprintln!("failed to find solution: {e}");
+                report_solution(solver);
+                return;
+            }
+        };
 
         println!(
-            "Guess: {}, Expected #guesses: {}, Expected ΔEntropy: {}, Remaining Possibilities: {}",
-            guess.to_string(),
+            "Guess: {}, Expected #guesses: {}, Expected ΔEntropy: {} {}, Remaining Possibilities: {}",
+            guess,
             solver.current_expected_score,
-            solver.current_guess_entropy,
+            solver.in_entropy_unit(solver.current_guess_entropy),
+            solver.entropy_unit(),
             initial_possibilities
         );
+        print_duplicate_hint(solver, &guess, options);
+        print_solve_odds(solver);
 
         // Ask the user for feedback
-        print!("Enter feedback (M = Match, P = Partial, N = No match, e.g. MPNPN): ");
-        io::stdout().flush().unwrap();
-        let mut feedback = String::new();
-        io::stdin()
-            .read_line(&mut feedback)
-            .expect("Failed to read input");
-        let feedback = feedback.trim().to_uppercase();
-
-        if feedback.len() != 5 {
-            eprintln!(
-                "Feedback must be exactly 5 characters (M/P/N). Got: {}",
-                feedback
-            );
-            std::process::exit(1);
+        let Some(feedback) = prompt(
+            "Enter feedback (M = Match, P = Partial, N = No match, e.g. MPNPN, or 2/1/0 or 🟩🟨⬛, ? if unsure, or just the tiles you remember, e.g. MPN), or constrain __E__ +AT -R, or list [N], or heatmap, or explain, or risk, or dist <word>: ",
+        ) else {
+            return; // EOF
+        };
+        let feedback = feedback.to_uppercase();
+
+        if let Some(spec) = strip_command(&feedback, "CONSTRAIN") {
+            apply_constraints(solver, spec);
+            continue;
+        }
+        if let Some(args) = strip_command(&feedback, "LIST") {
+            handle_list(solver, args);
+            continue;
+        }
+        if strip_command(&feedback, "HEATMAP").is_some() {
+            print_heatmap(solver);
+            continue;
+        }
+        if strip_command(&feedback, "EXPLAIN").is_some() {
+            print_explanation(solver);
+            continue;
+        }
+        if strip_command(&feedback, "RISK").is_some() {
+            print_risk_map(solver);
+            continue;
+        }
+        if let Some(args) = strip_command(&feedback, "DIST") {
+            print_pattern_distribution(solver, args);
+            continue;
         }
 
-        // Parse feedback into MatchResult
-        let parsed_feedback = match parse_feedback(&feedback) {
+        // Parse feedback into the patterns it could be
+        let parsed_feedback = match parse_uncertain_feedback(&feedback) {
             Ok(parsed) => parsed,
             Err(e) => {
-                eprintln!("{}", e);
-                std::process::exit(1);
+                eprintln!("{}, please re-enter.", e);
+                continue;
             }
         };
 
-        // Now filter possibilities manually using the parsed feedback
-        solver.guess(|_| parsed_feedback);
+        // The guess itself is the answer, however many words were still possible
+        if is_solved_by(&parsed_feedback) {
+            log.record(&guess, &parsed_feedback, 1);
+            println!("Solution Found: {}", guess);
+            let _ = solver.guess_any_of(&parsed_feedback); // counts the information it gave
+            print_information_summary(solver);
+            return;
+        }
 
-        let actual_entropy: f64 = f64::log2(initial_possibilities as f64)
-            - f64::log2(solver.current_possibilities.len() as f64);
+        // Now filter possibilities manually using the parsed feedback
+        if solver.guess_any_of(&parsed_feedback).is_err() {
+            eprintln!("Contradictory feedback: no words match it, please re-enter.");
+            continue;
+        }
+        log.record(&guess, &parsed_feedback, solver.current_possibilities.len());
 
+        let (_, actual) = solver
+            .information_gained()
+            .last()
+            .copied()
+            .unwrap_or_default();
         println!(
-            "New Remaining Possibilities: {}, Actual ΔEntropy: {}",
+            "New Remaining Possibilities: {}, Actual ΔEntropy: {} {}",
             solver.current_possibilities.len(),
-            actual_entropy
+            solver.in_entropy_unit(actual),
+            solver.entropy_unit()
         );
     }
 
+    report_solution(solver);
+    print_information_summary(solver);
+}
+
+/// Print the expected and actual information each guess of the game gained, and the total
+/// against the `log2(dictionary size)` needed to single out any word
+fn print_information_summary(solver: &WordleSolver) {
+    let information = solver.information_gained();
+    if information.is_empty() {
+        return;
+    }
+    let unit = solver.entropy_unit();
+    println!("Information per guess (expected → actual):");
+    for (turn, (expected, actual)) in information.iter().enumerate() {
+        let luck = actual - expected;
+        println!(
+            "  {}. {:.3} → {:.3} {unit} ({:+.3}, {})",
+            turn + 1,
+            solver.in_entropy_unit(*expected),
+            solver.in_entropy_unit(*actual),
+            solver.in_entropy_unit(luck),
+            match luck {
+                l if l > TIE_EPSILON => "lucky",
+                l if l < -TIE_EPSILON => "unlucky",
+                _ => "as expected",
+            }
+        );
+    }
+    let total: f64 = information.iter().map(|(_, actual)| actual).sum();
     println!(
-        "Solution Found: {}",
-        solver.dictionary[solver.current_possibilities[0]].to_string()
+        "Total: {:.3} of {:.3} {unit} (log of {} dictionary words)",
+        solver.in_entropy_unit(total),
+        unit.log(solver.dictionary.len() as f64),
+        solver.dictionary.len()
     );
 }
 
-/// Parse the user feedback string like "MPNPN" into MatchResult
-fn parse_feedback(feedback: &str) -> Result<MatchResult, String> {
-    let mut result = [MatchKind::NoMatch; 5];
-    for (i, c) in feedback.chars().enumerate() {
-        result[i] = match c {
-            'M' => MatchKind::Match,
-            'P' => MatchKind::Partial,
-            'N' => MatchKind::NoMatch,
-            _ => {
-                return Err(format!(
-                    "Invalid feedback character '{}'. Use only M, P, N.",
-                    c
-                ));
-            }
+/// Build the solver for an interactive game: the requested policy (or data-driven scoring
+/// when a training curve exists) and the `--green` letters, if any, already applied
+fn load_play_solver(source: &SolverSource, options: &PlayOptions) -> WordleSolver {
+    let (policy, curve) = resolve_policy(options.policy, source, options.bucket_width);
+    let mut solver = load_solver(source, policy, curve);
+    if let Some(greens) = &options.green {
+        if solver.filter_by_constraints(greens).is_err() {
+            eprintln!("❌  No dictionary word matches the --green letters");
+            std::process::exit(1);
         }
+        info!(
+            "Remaining Possibilities after --green: {}",
+            solver.current_possibilities.len()
+        );
     }
-    Ok(result)
+    solver
 }
 
-/// Where should the worker write its shard?
-#[derive(Debug, Clone, Copy)]
-enum RunKind {
-    Train, //  → ./train/training_data.{id}.csv
-    Test,  //  → ./test/testing_data.{id}.csv
-}
+/// With a one-word dictionary there is nothing to solve: say so, name the word and return
+/// true instead of playing a game
+fn announce_only_word(solver: &WordleSolver) -> bool {
+    if solver.dictionary.len() != 1 {
+        return false;
+    }
+    println!(
+        "The dictionary has a single word, so the answer is {}",
+        solver.dictionary[0]
+    );
+    true
+}
+
+/// Print the solution at the end of a game. If several words are left that the solver
+/// can't tell apart, list them all with their probabilities and recommend the likeliest
+/// rather than picking one silently.
+fn report_solution(solver: &WordleSolver) {
+    let candidates = solver.candidate_probabilities();
+    match candidates.as_slice() {
+        [] => eprintln!("failed to find solution: {}", SolverError::NoCandidates),
+        [(word, _)] => println!("Solution Found: {}", word),
+        [(likeliest, _), ..] => {
+            println!(
+                "{} words remain that the feedback can't separate:",
+                candidates.len()
+            );
+            for (word, probability) in &candidates {
+                println!("  {} {:>6.2}%", word, 100.0 * probability);
+            }
+            println!("Best bet: guess {}, the most likely", likeliest);
+        }
+    }
+}
+
+/// Advisory mode: the player reports each word they played and the feedback they got, and
+/// the solver tracks the remaining possibilities and suggests a next guess. The played word
+/// doesn't have to be the suggestion.
+fn assist_play(source: &SolverSource, options: &PlayOptions) {
+    let mut solver = load_play_solver(source, options);
+    let mut log = TranscriptLog::open(options.log.as_deref());
+    let mut timer = StepTimer::new(source.timing);
+
+    assist_loop(&mut solver, options, &mut log, &mut timer);
+    timer.report("assist");
+}
+
+/// Suggest guesses and read the played words and their feedback until one possibility
+/// remains
+fn assist_loop(
+    solver: &mut WordleSolver,
+    options: &PlayOptions,
+    log: &mut TranscriptLog,
+    timer: &mut StepTimer,
+) {
+    if announce_only_word(solver) {
+        return;
+    }
+    let prefixes = PrefixIndex::new(&solver.dictionary);
+    while solver.current_possibilities.len() > 1 {
+        print_candidates(solver, options);
+        match timer.time(|| solver.step()) {
+            Ok(suggestion) => {
+                println!(
+                    "Remaining Possibilities: {}, Suggested guess: {} (Expected ΔEntropy: {} {})",
+                    solver.current_possibilities.len(),
+                    suggestion,
+                    solver.in_entropy_unit(solver.current_guess_entropy),
+                    solver.entropy_unit()
+                );
+                print_duplicate_hint(solver, &suggestion, options);
+                print_solve_odds(solver);
+            }
+            Err(e) => println!(
+                "Remaining Possibilities: {}, no suggestion available: {e}",
+                solver.current_possibilities.len()
+            ),
+        }
+
+        let Some(line) = prompt(
+            "Enter the word you played (or a unique prefix) and its feedback (e.g. CRANE MPNPN): ",
+        ) else {
+            return; // EOF
+        };
+        let upper = line.to_uppercase();
+        if let Some(spec) = strip_command(&upper, "CONSTRAIN") {
+            apply_constraints(solver, spec);
+            continue;
+        }
+        if let Some(args) = strip_command(&upper, "LIST") {
+            handle_list(solver, args);
+            continue;
+        }
+        if strip_command(&upper, "HEATMAP").is_some() {
+            print_heatmap(solver);
+            continue;
+        }
+        if strip_command(&upper, "EXPLAIN").is_some() {
+            print_explanation(solver);
+            continue;
+        }
+        if strip_command(&upper, "RISK").is_some() {
+            print_risk_map(solver);
+            continue;
+        }
+        if let Some(args) = strip_command(&upper, "DIST") {
+            print_pattern_distribution(solver, args);
+            continue;
+        }
+        let parsed = expand_word_prefix(&line, &prefixes)
+            .and_then(|line| parse_played_word(&line, solver.alphabet()));
+        let (word, feedback) = match parsed {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                eprintln!("{}", e);
+                continue;
+            }
+        };
+
+        if is_solved_by(&feedback) {
+            log.record(&word, &feedback, 1);
+            println!("Solved: {}", word);
+            solver.set_guess(word);
+            let _ = solver.guess_any_of(&feedback); // counts the information it gave
+            print_information_summary(solver);
+            return;
+        }
+        solver.set_guess(word.clone());
+        match solver.guess_any_of(&feedback) {
+            Ok(()) => log.record(&word, &feedback, solver.current_possibilities.len()),
+            Err(_) => eprintln!("Contradictory feedback: no words match it, please re-enter."),
+        }
+    }
+
+    report_solution(solver);
+    print_information_summary(solver);
+}
+
+/// Play the host adversarially, like Absurdle: answer each guess with the feedback that
+/// keeps the most words possible, until only one is left
+fn absurdle_play(source: &SolverSource) {
+    let mut solver = load_solver(source, Policy::MaximizeEntropy, Vec::new());
+    let mut guesses = 0;
+
+    while solver.current_possibilities.len() > 1 {
+        let message = format!(
+            "Remaining Possibilities: {}, enter your guess: ",
+            solver.current_possibilities.len()
+        );
+        let Some(line) = prompt(&message) else {
+            return; // EOF
+        };
+        let guess = match WordEncoding::from_string(&line, solver.alphabet()) {
+            Ok(guess) => guess,
+            Err(e) => {
+                eprintln!("{}", e);
+                continue;
+            }
+        };
+
+        let feedback = solver.adversarial_feedback(&guess);
+        guesses += 1;
+        println!(
+            "{}  {}",
+            format_feedback(&feedback),
+            format_emoji(&feedback)
+        );
+        if is_solved(&feedback) {
+            println!("Solved {} in {} guesses", guess, guesses);
+            return;
+        }
+        solver
+            .apply(&guess, feedback)
+            .expect("the host only gives feedback some possibility produces");
+    }
+
+    let answer = &solver.dictionary[solver.current_possibilities[0]];
+    println!(
+        "Only {} remains after {} guesses; the host can't dodge it any more",
+        answer, guesses
+    );
+}
+
+/// Solve `boards` boards at once (Dordle, Quordle, ...), asking for each unsolved board's
+/// feedback after every guess
+fn multi_play(source: &SolverSource, boards: usize) {
+    let solver = load_solver(source, Policy::MaximizeEntropy, Vec::new());
+    let mut multi = MultiSolver::new(solver, boards);
+
+    while !multi.all_solved() {
+        let guess = match multi.step() {
+            Ok(guess) => guess,
+            Err(e) => {
+                eprintln!("failed to find solution: {e}");
+                return;
+            }
+        };
+        println!(
+            "Guess: {}, Expected ΣΔEntropy: {} {}",
+            guess,
+            source.entropy_unit.from_bits(multi.current_guess_entropy),
+            source.entropy_unit
+        );
+
+        let mut feedback: Vec<Option<MatchResult>> = vec![None; boards];
+        for (board, slot) in feedback.iter_mut().enumerate() {
+            if multi.is_solved(board) {
+                continue;
+            }
+            let remaining = multi.boards[board].current_possibilities.len();
+            while slot.is_none() {
+                let message = format!(
+                    "Board {} ({} remaining) feedback (e.g. MPNPN, 21010 or 🟩🟨⬛🟨⬛): ",
+                    board + 1,
+                    remaining
+                );
+                let Some(line) = prompt(&message) else {
+                    return; // EOF
+                };
+                match parse_feedback(&line.to_uppercase()) {
+                    Ok(parsed) => *slot = Some(parsed),
+                    Err(e) => eprintln!("{}", e),
+                }
+            }
+        }
+        if multi.guess(|board, _| feedback[board].unwrap()).is_err() {
+            eprintln!("Contradictory feedback: no words match it on some board, please re-enter.");
+            continue;
+        }
+
+        for (board, solver) in multi.boards.iter().enumerate() {
+            if multi.is_solved(board) && feedback[board].is_some() {
+                println!("Board {} solved: {}", board + 1, guess);
+            } else if solver.current_possibilities.len() == 1 && feedback[board].is_some() {
+                println!(
+                    "Board {} must be {}",
+                    board + 1,
+                    solver.dictionary[solver.current_possibilities[0]]
+                );
+            }
+        }
+    }
+}
+
+/// List the remaining words with their probabilities once there are few enough of them
+fn print_candidates(solver: &WordleSolver, options: &PlayOptions) {
+    if solver.current_possibilities.len() >= options.show_candidates_below {
+        return;
+    }
+    for (word, probability) in solver.candidate_probabilities() {
+        println!("  {} {:>6.2}%", word, 100.0 * probability);
+    }
+}
+
+/// Handle `constrain <spec>` typed at a prompt, e.g. `constrain __E__ +AT -R`
+fn apply_constraints(solver: &mut WordleSolver, spec: &str) {
+    match parse_constraints(spec) {
+        Ok(constraints) => match solver.filter_by_constraints(&constraints) {
+            Ok(()) => println!(
+                "Remaining Possibilities after constraints: {}",
+                solver.current_possibilities.len()
+            ),
+            Err(e) => eprintln!("{}", e),
+        },
+        Err(e) => eprintln!("{}", e),
+    }
+}
+
+/// Print `message` and read one trimmed line from stdin, or `None` at end of input
+fn prompt(message: &str) -> Option<String> {
+    print!("{}", message);
+    io::stdout().flush().unwrap();
+    let mut line = String::new();
+    match io::stdin().read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(line.trim().to_string()),
+    }
+}
+
+/// Parse "<word> <feedback>" (or "<word>:<feedback>") as entered in assist mode, into the
+/// word and every pattern the feedback could be (see `parse_uncertain_feedback`)
+fn parse_played_word(
+    line: &str,
+    alphabet: &Alphabet,
+) -> Result<(WordEncoding, Vec<MatchResult>), String> {
+    let mut parts = line
+        .split(|c: char| c.is_whitespace() || c == ':')
+        .filter(|p| !p.is_empty());
+    let (Some(word), Some(feedback), None) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(format!(
+            "Expected a word and its feedback, e.g. CRANE MPNPN. Got: {}",
+            line
+        ));
+    };
+    Ok((
+        WordEncoding::from_string(word, alphabet)?,
+        parse_uncertain_feedback(&feedback.to_uppercase())?,
+    ))
+}
+
+// Most words listed when a typed prefix is ambiguous
+const PREFIX_MATCHES_SHOWN: usize = 10;
+
+/// Expand a word shorter than five letters at the start of an assist-mode line (e.g.
+/// "CRA MPNPN") to the one dictionary word starting with it; other lines are unchanged.
+/// Fails, listing the options, if no word or several words start with it.
+fn expand_word_prefix(line: &str, prefixes: &PrefixIndex) -> Result<String, String> {
+    let line = line.trim_start();
+    let end = line
+        .find(|c: char| c.is_whitespace() || c == ':')
+        .unwrap_or(line.len());
+    let (prefix, rest) = line.split_at(end);
+    if prefix.is_empty() || prefix.chars().count() >= 5 {
+        return Ok(line.to_string());
+    }
+
+    let prefix = prefix.to_uppercase();
+    match prefixes.matches(&prefix) {
+        [] => Err(format!("No dictionary word starts with {}", prefix)),
+        [(word, _)] => {
+            println!("{} → {}", prefix, word);
+            Ok(format!("{word}{rest}"))
+        }
+        several => {
+            let shown: Vec<&str> = several
+                .iter()
+                .take(PREFIX_MATCHES_SHOWN)
+                .map(|(word, _)| word.as_str())
+                .collect();
+            let more = match several.len() - shown.len() {
+                0 => String::new(),
+                n => format!(" and {n} more"),
+            };
+            Err(format!(
+                "{} starts {} words: {}{}; type more letters",
+                prefix,
+                several.len(),
+                shown.join(", "),
+                more
+            ))
+        }
+    }
+}
+
+/// Whether feedback that could be any of `patterns` certainly solved the game
+fn is_solved_by(patterns: &[MatchResult]) -> bool {
+    matches!(patterns, [pattern] if is_solved(pattern))
+}
+
+/// `patterns` as letter feedback, with `?` at the tiles they disagree on
+fn format_uncertain_feedback(patterns: &[MatchResult]) -> String {
+    let mut letters: Vec<char> = format_feedback(&patterns[0]).chars().collect();
+    for pattern in &patterns[1..] {
+        for (letter, other) in letters.iter_mut().zip(format_feedback(pattern).chars()) {
+            if *letter != other {
+                *letter = '?';
+            }
+        }
+    }
+    letters.into_iter().collect()
+}
+
+/// Solve every secret in the dictionary with the chosen policy and print summary metrics.
+/// Nothing is written to disk.
+fn run_benchmark(
+    policy: PolicyArg,
+    max_guesses: usize,
+    start: Option<&str>,
+    source: &SolverSource,
+) {
+    let mut solver = load_game_solver(policy, max_guesses, start, source);
+    let secrets: Vec<usize> = (0..solver.dictionary.len()).collect();
+    run_games(
+        &mut solver,
+        &secrets,
+        max_guesses,
+        "Benchmark",
+        source.timing,
+    );
+}
+
+/// Solve every secret in the dictionary under each of `policies` and print a table of the
+/// average guesses (over games solved within `max_guesses`), the most guesses any secret
+/// needs and the share of games not solved within the budget. Games are played to the end,
+/// so the worst case can exceed the budget. The dictionary is loaded once for all policies.
+fn run_comparison(
+    policies: &[PolicyArg],
+    max_guesses: usize,
+    start: Option<&str>,
+    source: &SolverSource,
+) {
+    let mut solver = load_game_solver(policies[0], max_guesses, start, source);
+    let mut timer = StepTimer::new(source.timing);
+    let secrets = solver.dictionary.len();
+    // Guesses are never repeated, so no game needs more guesses than there are words
+    let limit = secrets;
+
+    let mut rows = Vec::new();
+    for &policy_arg in policies {
+        let (policy, curve) = resolve_policy(Some(policy_arg), source, DEFAULT_BUCKET_WIDTH);
+        solver.set_policy(policy, curve);
+
+        let started = Instant::now();
+        let mut distribution = GuessDistribution::new(max_guesses);
+        let mut worst: Option<usize> = None;
+        for secret_idx in 0..secrets {
+            let guesses = play_game(&mut solver, secret_idx, limit, &mut timer);
+            worst = worst.max(guesses);
+            distribution.record(guesses);
+        }
+        rows.push((policy_arg, distribution, worst, started.elapsed()));
+    }
+
+    println!("\nPolicy comparison over {secrets} secrets:");
+    println!(
+        "  {:<12} {:>12} {:>6} {:>8} {:>8}",
+        "policy", "avg guesses", "worst", "failed", "time"
+    );
+    for (policy, distribution, worst, elapsed) in rows {
+        let name = policy.to_possible_value().unwrap();
+        println!(
+            "  {:<12} {:>12.4} {:>6} {:>7.2}% {:>8}",
+            name.get_name(),
+            distribution.average_guesses(),
+            worst.map_or("-".to_string(), |w| w.to_string()),
+            100.0 * distribution.failures as f64 / secrets as f64,
+            format_duration(elapsed)
+        );
+    }
+    timer.report("Comparison");
+}
+
+/// Solve `games` secrets drawn at random (with replacement) from the dictionary and print
+/// the same metrics as `run_benchmark`. The same `seed` draws the same secrets; without one
+/// a seed is picked and printed, so the run can be repeated.
+fn run_simulation(
+    games: usize,
+    seed: Option<u64>,
+    policy: PolicyArg,
+    max_guesses: usize,
+    start: Option<&str>,
+    source: &SolverSource,
+) {
+    let mut solver = load_game_solver(policy, max_guesses, start, source);
+    let seed = seed.unwrap_or_else(rand::random);
+    println!("Simulating {} games with seed {}", games, seed);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let secrets: Vec<usize> = (0..games)
+        .map(|_| rng.gen_range(0..solver.dictionary.len()))
+        .collect();
+    run_games(
+        &mut solver,
+        &secrets,
+        max_guesses,
+        "Simulation",
+        source.timing,
+    );
+}
+
+// Solver for `bench`/`simulate` games with the chosen policy, budget and opener
+fn load_game_solver(
+    policy: PolicyArg,
+    max_guesses: usize,
+    start: Option<&str>,
+    source: &SolverSource,
+) -> WordleSolver {
+    let (policy, curve) = resolve_policy(Some(policy), source, DEFAULT_BUCKET_WIDTH);
+
+    let mut solver = load_solver(source, policy, curve);
+    solver.set_max_guesses(max_guesses);
+    apply_start_word(&mut solver, start);
+    solver
+}
+
+/// Play a game against each of `secrets` (dictionary indices) and print the guess-count
+/// distribution under `title`, with the time taken
+fn run_games(
+    solver: &mut WordleSolver,
+    secrets: &[usize],
+    max_guesses: usize,
+    title: &str,
+    timing: bool,
+) {
+    let mut distribution = GuessDistribution::new(max_guesses);
+    let mut timer = StepTimer::new(timing);
+    let started = Instant::now();
+
+    for &secret_idx in secrets {
+        distribution.record(play_game(solver, secret_idx, max_guesses, &mut timer));
+    }
+
+    let elapsed = started.elapsed();
+    println!("\n{} over {} secrets:", title, secrets.len());
+    distribution.print();
+    println!(
+        "Total time: {}, {:.1} ms per game",
+        format_duration(elapsed),
+        elapsed.as_secs_f64() * 1000.0 / secrets.len() as f64
+    );
+    timer.report(title);
+}
+
+/// Play one game against dictionary word `secret_idx`, giving up after `limit` guesses.
+/// Returns the guesses it took, or `None` if it wasn't solved.
+fn play_game(
+    solver: &mut WordleSolver,
+    secret_idx: usize,
+    limit: usize,
+    timer: &mut StepTimer,
+) -> Option<usize> {
+    let oracle = FixedSecret(solver.dictionary[secret_idx].clone());
+    solver.reset();
+
+    let mut guesses = 0;
+    loop {
+        let guess = timer.time(|| solver.step()).ok()?;
+        guesses += 1;
+
+        if guess == oracle.0 {
+            return Some(guesses);
+        }
+        if guesses == limit {
+            return None;
+        }
+        solver.guess_with(&oracle).ok()?;
+    }
+}
+
+/// Solve every secret in the dictionary, playing past the budget, and report the secret
+/// needing the most guesses and how many secrets don't fit in `max_guesses`
+fn run_worst_case(
+    policy: PolicyArg,
+    max_guesses: usize,
+    start: Option<&str>,
+    source: &SolverSource,
+) {
+    let mut solver = load_game_solver(policy, max_guesses, start, source);
+    let mut timer = StepTimer::new(source.timing);
+    let started = Instant::now();
+
+    // Guesses are never repeated, so no game needs more guesses than there are words
+    let limit = solver.dictionary.len();
+    let mut worst: Option<(usize, usize)> = None; // (guesses, secret index)
+    let mut tied = 0;
+    let mut over_budget = 0;
+    let mut unsolved = Vec::new();
+    for secret_idx in 0..solver.dictionary.len() {
+        let Some(guesses) = play_game(&mut solver, secret_idx, limit, &mut timer) else {
+            unsolved.push(secret_idx);
+            continue;
+        };
+        if guesses > max_guesses {
+            over_budget += 1;
+        }
+        match worst {
+            Some((most, _)) if guesses < most => {}
+            Some((most, _)) if guesses == most => tied += 1,
+            _ => {
+                worst = Some((guesses, secret_idx));
+                tied = 0;
+            }
+        }
+    }
+
+    let elapsed = started.elapsed();
+    println!("\nWorst case over {} secrets:", solver.dictionary.len());
+    match worst {
+        Some((guesses, secret_idx)) => {
+            print!(
+                "  {} needs {} guesses",
+                solver.dictionary[secret_idx], guesses
+            );
+            match tied {
+                0 => println!(),
+                n => println!(" ({} other secrets need as many)", n),
+            }
+        }
+        None => println!("  no secret was solved"),
+    }
+    println!(
+        "  {} secrets need more than {} guesses",
+        over_budget, max_guesses
+    );
+    if !unsolved.is_empty() {
+        let words: Vec<String> = unsolved
+            .iter()
+            .map(|i| solver.dictionary[*i].to_string())
+            .collect();
+        println!(
+            "  {} secrets couldn't be solved: {}",
+            words.len(),
+            words.join(" ")
+        );
+    }
+    println!("Total time: {}", format_duration(elapsed));
+    timer.report("Worst case");
+}
+
+/// How many games were solved in each number of guesses, and how many weren't solved
+struct GuessDistribution {
+    solved_in: Vec<usize>, // solved_in[g - 1] counts games solved in exactly g guesses
+    failures: usize,
+}
+
+impl GuessDistribution {
+    fn new(max_guesses: usize) -> GuessDistribution {
+        GuessDistribution {
+            solved_in: vec![0; max_guesses],
+            failures: 0,
+        }
+    }
+
+    /// Count a game solved in `guesses`, or a failure if it wasn't solved (or not within
+    /// the budget)
+    fn record(&mut self, guesses: Option<usize>) {
+        match guesses {
+            Some(g) if (1..=self.solved_in.len()).contains(&g) => self.solved_in[g - 1] += 1,
+            _ => self.failures += 1,
+        }
+    }
+
+    /// Average guesses over the solved games
+    fn average_guesses(&self) -> f64 {
+        let solved: usize = self.solved_in.iter().sum();
+        let total_guesses: usize = self
+            .solved_in
+            .iter()
+            .enumerate()
+            .map(|(i, count)| (i + 1) * count)
+            .sum();
+        total_guesses as f64 / solved as f64
+    }
+
+    fn print(&self) {
+        let solved: usize = self.solved_in.iter().sum();
+        let games = solved + self.failures;
+
+        for (i, count) in self.solved_in.iter().enumerate() {
+            println!("  {} guesses: {}", i + 1, count);
+        }
+        println!("  failed:    {}", self.failures);
+        println!("Success rate: {:.2}%", 100.0 * solved as f64 / games as f64);
+        println!(
+            "Average guesses (solved games): {:.4}",
+            self.average_guesses()
+        );
+    }
+}
+
+/// The solver's guess from one game state and, for every feedback it can get that doesn't
+/// solve the game, the node reached
+#[derive(Serialize)]
+struct DecisionNode {
+    guess: String,
+    remaining: usize, // Possibilities before the guess
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    next: BTreeMap<String, DecisionNode>, // Keyed by feedback, e.g. "MPNPN"
+}
+
+/// Write the solver's strategy to `out` as nested JSON: its guess from a fresh game (or
+/// `start`), then its replies to every reachable feedback, `depth` guesses deep.
+fn export_decision_tree(source: &SolverSource, start: Option<&str>, depth: usize, out: &Path) {
+    let (policy, curve) = resolve_policy(None, source, DEFAULT_BUCKET_WIDTH);
+    let mut solver = load_solver(source, policy, curve);
+    apply_start_word(&mut solver, start);
+
+    let Some(tree) = decision_node(&mut solver, depth) else {
+        eprintln!("❌  No guess available from the starting state");
+        std::process::exit(1);
+    };
+
+    let mut writer = match File::create(out) {
+        Ok(file) => BufWriter::new(file),
+        Err(e) => {
+            eprintln!("❌  Cannot create {}: {e}", out.display());
+            std::process::exit(1);
+        }
+    };
+    let result = serde_json::to_writer_pretty(&mut writer, &tree)
+        .map_err(io::Error::from)
+        .and_then(|_| writeln!(writer))
+        .and_then(|_| writer.flush());
+    if let Err(e) = result {
+        eprintln!("❌  Failed to write decision tree: {e}");
+        std::process::exit(1);
+    }
+    println!("Wrote decision tree to {}", out.display());
+}
+
+// The subtree `depth` guesses deep from the solver's state, or None if it has no guess
+fn decision_node(solver: &mut WordleSolver, depth: usize) -> Option<DecisionNode> {
+    let guess = solver.step().ok()?;
+    let remaining = solver.current_possibilities.len();
+
+    // Each feedback the guess can get, in the order the possibilities give it
+    let mut patterns: Vec<MatchResult> = Vec::new();
+    for i in &solver.current_possibilities {
+        let pattern = guess.match_result(&solver.dictionary[*i]);
+        if !is_solved(&pattern) && !patterns.contains(&pattern) {
+            patterns.push(pattern);
+        }
+    }
+
+    let mut next = BTreeMap::new();
+    if depth > 1 {
+        for pattern in patterns {
+            let mut branch = solver.clone();
+            if branch.apply(&guess, pattern).is_ok()
+                && let Some(node) = decision_node(&mut branch, depth - 1)
+            {
+                next.insert(format_feedback(&pattern), node);
+            }
+        }
+    }
+
+    Some(DecisionNode {
+        guess: guess.to_string(),
+        remaining,
+        next,
+    })
+}
+
+/// Print the solver's opening guess under `policy` and the configured prior, and with `top`
+/// the best that many openers, without starting a game.
+fn print_first_guess(policy: PolicyArg, top: Option<usize>, source: &SolverSource) {
+    let (policy, curve) = resolve_policy(Some(policy), source, DEFAULT_BUCKET_WIDTH);
+    let mut solver = load_solver(source, policy, curve);
+
+    let guess = match solver.step() {
+        Ok(guess) => guess,
+        Err(e) => {
+            eprintln!("❌  No opening guess: {e}");
+            std::process::exit(1);
+        }
+    };
+    let unit = solver.entropy_unit();
+    print!(
+        "Best first guess ({:?}): {} ({:.4} {unit}",
+        policy,
+        guess,
+        solver.in_entropy_unit(solver.current_guess_entropy)
+    );
+    if solver.current_expected_score.is_finite() {
+        print!(", expected score {:.4}", solver.current_expected_score);
+    }
+    println!(")");
+
+    if let Some(top) = top {
+        for (rank, (word, entropy, expected_score)) in
+            solver.ranked_guesses(top).into_iter().enumerate()
+        {
+            let entropy = solver.in_entropy_unit(entropy);
+            match expected_score.is_finite() {
+                true => println!(
+                    "{:>4}. {}  {:.4} {unit}  score {:.4}",
+                    rank + 1,
+                    word,
+                    entropy,
+                    expected_score
+                ),
+                false => println!("{:>4}. {}  {:.4} {unit}", rank + 1, word, entropy),
+            }
+        }
+    }
+}
+
+/// Report the dictionary words whose prior is below `epsilon`: in a fresh game they are
+/// practically never expected, so the solver treats them as all but impossible answers.
+/// Lists up to `sample` of them, spread evenly through the dictionary.
+fn print_prior_coverage(source: &SolverSource, epsilon: f64, sample: usize) {
+    let solver = load_solver(source, Policy::MaximizeEntropy, Vec::new());
+    let prior = solver.prior();
+
+    let negligible: Vec<usize> = (0..prior.len()).filter(|i| prior[*i] < epsilon).collect();
+    let mass = negligible.iter().fold(0.0, |mass, i| mass + prior[*i]);
+    println!(
+        "{} of {} words ({:.1}%) have a prior below {:e}, together {:.2e} of the mass",
+        negligible.len(),
+        prior.len(),
+        100.0 * negligible.len() as f64 / prior.len().max(1) as f64,
+        epsilon,
+        mass
+    );
+    let (Some(first), Some(last)) = (negligible.first(), negligible.last()) else {
+        return;
+    };
+    println!(
+        "The first is {} (word {}), the last {} (word {})",
+        solver.dictionary[*first],
+        first + 1,
+        solver.dictionary[*last],
+        last + 1
+    );
+
+    let shown = sample.min(negligible.len());
+    if shown > 0 {
+        println!("Sample:");
+    }
+    for k in 0..shown {
+        let i = negligible[k * negligible.len() / shown];
+        println!("  {:>6}. {}  {:.3e}", i + 1, solver.dictionary[i], prior[i]);
+    }
+}
+
+/// Print summary statistics of the dictionary: positional and overall letter frequencies,
+/// words with repeated letters, and the best opener by entropy under a uniform prior.
+fn print_dictionary_stats(source: &SolverSource) {
+    let solver = load_solver(source, Policy::MaximizeEntropy, Vec::new());
+    let words: &[WordEncoding] = &solver.dictionary;
+    let alphabet = solver.alphabet();
+
+    let mut by_position = vec![[0usize; 5]; alphabet.len()];
+    let mut overall = vec![0usize; alphabet.len()];
+    let mut duplicate_words = 0;
+    for word in words {
+        for (i, c) in word.positions.iter().enumerate() {
+            by_position[alphabet.index(*c).unwrap()][i] += 1;
+        }
+        for (total, count) in overall.iter_mut().zip(word.frequencies.iter()) {
+            *total += *count as usize;
+        }
+        if word.frequencies.iter().any(|count| *count > 1) {
+            duplicate_words += 1;
+        }
+    }
+
+    println!("\nLetter frequency by position:");
+    println!("  {:>6} {:>6} {:>6} {:>6} {:>6}", 1, 2, 3, 4, 5);
+    for (letter, counts) in alphabet.letters().iter().zip(by_position.iter()) {
+        print!("{}", letter);
+        for count in counts {
+            print!(" {:>6}", count);
+        }
+        println!();
+    }
+
+    let total_letters: usize = overall.iter().sum();
+    let mut ranked: Vec<(char, usize)> = alphabet.letters().iter().copied().zip(overall).collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    println!("\nOverall letter frequency:");
+    for (letter, count) in ranked {
+        println!(
+            "{} {:>6} ({:.2}%)",
+            letter,
+            count,
+            100.0 * count as f64 / total_letters as f64
+        );
+    }
+
+    println!(
+        "\nWords with duplicate letters: {} of {} ({:.1}%)",
+        duplicate_words,
+        words.len(),
+        100.0 * duplicate_words as f64 / words.len() as f64
+    );
+
+    // Most informative opener against a uniform prior
+    let uniform = 1.0 / words.len() as f64;
+    // Ties go to the word with the higher positional letter score, as in `step()`
+    let tie_key = |i: usize| {
+        (
+            std::cmp::Reverse(solver.letter_scores()[i]),
+            &words[i].positions,
+        )
+    };
+    let mut best: Option<(usize, f64)> = None;
+    for (i, guess) in words.iter().enumerate() {
+        let match_results: Vec<(Pattern, f64)> = words
+            .iter()
+            .map(|secret| (guess.match_pattern(secret), uniform))
+            .collect();
+        let entropy =
+            WordleSolver::compute_entropy(WordleSolver::compute_match_pattern_pd(&match_results));
+        let is_better = match best {
+            None => true,
+            Some((best_i, best_entropy)) => {
+                entropy > best_entropy + TIE_EPSILON
+                    || (entropy >= best_entropy - TIE_EPSILON && tie_key(i) < tie_key(best_i))
+            }
+        };
+        if is_better {
+            best = Some((i, entropy));
+        }
+    }
+    if let Some((i, entropy)) = best {
+        let guess = &words[i];
+        println!(
+            "Most informative starting word (uniform prior): {} ({:.4} {})",
+            guess,
+            solver.in_entropy_unit(entropy),
+            solver.entropy_unit()
+        );
+    }
+}
+
+/// Where solvers load their dictionary and prior from, as given by global CLI options
+struct SolverSource<'a> {
+    dictionary: PathBuf,
+    cache_dir: Option<&'a Path>,
+    prior: PriorConfig,
+    answers: Option<PathBuf>, // Likely-answers list layered on the prior
+    alphabet: Alphabet,
+    exact: bool,
+    lookahead: usize,
+    hard_mode: bool,
+    guess_threshold: f64, // Bits below which the score policy only guesses possibilities
+    commit_threshold: f64, // Prior above which the score policy guesses a possibility outright
+    entropy_unit: EntropyUnit, // Unit entropies are printed and written to shards in
+    timing: bool,         // Time each step() (see `StepTimer`)
+    lambda: f64,          // Weight of the expected score under the blend policy
+    output_dir: &'a Path, // Where the train/ and test/ shard directories live
+}
+
+impl<'a> SolverSource<'a> {
+    /// Resolve the dictionary against the current directory, exiting with a clear message
+    /// if the file doesn't exist or `alphabet` is invalid. Everything else starts at the
+    /// defaults: no cache, rank prior, no answers list, greedy pruned search, normal mode,
+    /// no guess threshold, no timing.
+    fn new(dictionary: &Path, alphabet: Option<&str>) -> Self {
+        let dictionary = std::env::current_dir()
+            .map(|cwd| cwd.join(dictionary).components().collect())
+            .unwrap_or_else(|_| dictionary.to_path_buf());
+        if !dictionary.is_file() {
+            eprintln!(
+                "❌  Dictionary not found: {} (choose one with --dictionary <path> or --lang <code>)",
+                dictionary.display()
+            );
+            std::process::exit(1);
+        }
+
+        let alphabet = match alphabet
+            .map(Alphabet::new)
+            .unwrap_or_else(|| Ok(Alphabet::english()))
+        {
+            Ok(alphabet) => alphabet,
+            Err(e) => {
+                eprintln!("❌  Invalid --alphabet: {e}");
+                std::process::exit(1);
+            }
+        };
+
+        SolverSource {
+            dictionary,
+            cache_dir: None,
+            prior: PriorConfig::Rank,
+            answers: None,
+            alphabet,
+            exact: false,
+            lookahead: 1,
+            hard_mode: false,
+            guess_threshold: 0.0,
+            commit_threshold: COMMIT_THRESHOLD,
+            entropy_unit: EntropyUnit::Bits,
+            timing: false,
+            lambda: DEFAULT_LAMBDA,
+            output_dir: Path::new("."),
+        }
+    }
+
+    /// Global options that worker processes must share with their parent
+    fn to_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "--dictionary".to_string(),
+            self.dictionary.display().to_string(),
+        ];
+        match &self.prior {
+            PriorConfig::Frequencies(path) => {
+                args.push("--frequencies".to_string());
+                args.push(path.display().to_string());
+            }
+            PriorConfig::Zipf(path) => {
+                args.push("--zipf".to_string());
+                args.push(path.display().to_string());
+            }
+            PriorConfig::Weights(path, default_weight) => {
+                args.push("--weights".to_string());
+                args.push(path.display().to_string());
+                args.push("--default-weight".to_string());
+                args.push(default_weight.to_string());
+            }
+            PriorConfig::Rank => {}
+        }
+        if let Some(path) = &self.answers {
+            args.push("--answers".to_string());
+            args.push(path.display().to_string());
+        }
+        match self.cache_dir {
+            Some(dir) => {
+                args.push("--cache-dir".to_string());
+                args.push(dir.display().to_string());
+            }
+            None => args.push("--no-cache".to_string()),
+        }
+        if self.alphabet != Alphabet::english() {
+            args.push("--alphabet".to_string());
+            args.push(self.alphabet.letters().iter().collect());
+        }
+        if self.exact {
+            args.push("--exact".to_string());
+        }
+        if self.hard_mode {
+            args.push("--hard".to_string());
+        }
+        if self.guess_threshold > 0.0 {
+            args.push("--guess-threshold".to_string());
+            args.push(self.guess_threshold.to_string());
+        }
+        if self.commit_threshold != COMMIT_THRESHOLD {
+            args.push("--commit-threshold".to_string());
+            args.push(self.commit_threshold.to_string());
+        }
+        if self.entropy_unit != EntropyUnit::Bits {
+            args.push("--entropy-unit".to_string());
+            args.push(self.entropy_unit.to_string());
+        }
+        if self.timing {
+            args.push("--timing".to_string());
+        }
+        if self.lookahead != 1 {
+            args.push("--lookahead".to_string());
+            args.push(self.lookahead.to_string());
+        }
+        if self.output_dir != Path::new(".") {
+            args.push("--output-dir".to_string());
+            args.push(self.output_dir.display().to_string());
+        }
+        match verbosity() {
+            Verbosity::Quiet => args.push("--quiet".to_string()),
+            Verbosity::Normal => {}
+            Verbosity::Verbose => args.push("--verbose".to_string()),
+        }
+        args
+    }
+}
+
+/// Build a solver from the configured dictionary and prior, exiting on failure
+fn load_solver(source: &SolverSource, policy: Policy, curve: Vec<Bucket>) -> WordleSolver {
+    verbose!(
+        "Dictionary {}, search: exact {}, lookahead {}, hard mode {}, guess threshold {} bits, commit threshold {}",
+        source.dictionary.display(),
+        source.exact,
+        source.lookahead,
+        source.hard_mode,
+        source.guess_threshold,
+        source.commit_threshold
+    );
+    let mut config = SolverConfig::from_file(&source.dictionary)
+        .alphabet(source.alphabet.clone())
+        .policy(policy)
+        .expected_moves_curve(curve)
+        .prior(source.prior.clone())
+        .exact(source.exact)
+        .lookahead(source.lookahead)
+        .hard_mode(source.hard_mode)
+        .guess_threshold(source.guess_threshold)
+        .commit_threshold(source.commit_threshold)
+        .entropy_unit(source.entropy_unit);
+    if let Some(dir) = source.cache_dir {
+        config = config.cache_dir(dir);
+    }
+    if let Some(path) = &source.answers {
+        config = config.answers(path);
+    }
+
+    match config.build() {
+        Ok(solver) => solver,
+        Err(e) => {
+            eprintln!("failed to initialize WordleSolver: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Force the solver's opening guess, exiting with a message if the word can't be used
+fn apply_start_word(solver: &mut WordleSolver, start: Option<&str>) {
+    if let Some(word) = start
+        && let Err(e) = solver.set_start_word(word)
+    {
+        eprintln!("❌  {e}");
+        std::process::exit(1);
+    }
+}
+
+/// Where should the worker write its shard?
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum RunKind {
+    Train, //  → <output dir>/train/training_data.{id}.csv
+    Test,  //  → <output dir>/test/testing_data.{id}.csv
+}
 
 impl RunKind {
-    fn dir(&self) -> &'static str {
+    fn dir(&self, output_dir: &Path) -> PathBuf {
+        output_dir.join(match self {
+            RunKind::Train => "train",
+            RunKind::Test => "test",
+        })
+    }
+    fn prefix(&self) -> &'static str {
         match self {
-            RunKind::Train => "./train",
-            RunKind::Test => "./test",
+            RunKind::Train => "training",
+            RunKind::Test => "testing",
         }
     }
-    fn shard_name(&self, id: usize) -> String {
+    fn shard_name(&self, output_dir: &Path, id: usize) -> String {
         format!(
             "{}/{}_data.{}.csv",
-            self.dir(),
-            match self {
-                RunKind::Train => "training",
-                RunKind::Test => "testing",
-            },
+            self.dir(output_dir).display(),
+            self.prefix(),
             id
         )
     }
+    fn shards_glob(&self, output_dir: &Path) -> String {
+        format!(
+            "{}/{}_data*.csv",
+            self.dir(output_dir).display(),
+            self.prefix()
+        )
+    }
 }
 
-fn run_generic_worker(kind: RunKind, worker_id: usize, total_workers: usize) {
-    std::fs::create_dir_all(kind.dir()).expect("cannot create output dir");
-
-    let shard_name = kind.shard_name(worker_id);
-    let mut writer = BufWriter::new(
-        OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&shard_name)
-            .expect("cannot open shard"),
-    );
-    if writer.get_ref().metadata().unwrap().len() == 0 {
-        writeln!(writer, "secret_idx,entropy,moves_remaining").unwrap();
+fn run_generic_worker(
+    kind: RunKind,
+    worker_id: usize,
+    total_workers: usize,
+    options: &WorkerOptions,
+    source: &SolverSource,
+) {
+    install_interrupt_handler();
+    let mut solver = load_worker_solver(source);
+    if let Err(e) = run_shard(&mut solver, kind, worker_id, total_workers, options, source) {
+        eprintln!(
+            "❌  [{:?} worker {}] cannot write shard: {e}",
+            kind, worker_id
+        );
+        std::process::exit(1);
     }
+}
+
+// Set by Ctrl-C; workers check it between secrets so a shard never ends in a partial row
+static STOP_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+fn install_interrupt_handler() {
+    ctrlc::set_handler(|| STOP_REQUESTED.store(true, Ordering::SeqCst))
+        .expect("cannot install Ctrl-C handler");
+}
 
-    let mut solver = WordleSolver::intialise(
-        &"./words_5_letters.txt".to_owned(),
-        Policy::MaximizeEntropy,
-        Vec::new(),
-    )
-    .unwrap();
+fn interrupted() -> bool {
+    STOP_REQUESTED.load(Ordering::SeqCst)
+}
 
-    let max_secrets = 1_500.min(solver.dictionary.len());
-    for secret_idx in (0..max_secrets).filter(|i| i % total_workers == worker_id) {
-        let secret = solver.dictionary[secret_idx];
-        solver.reset();
-        let mut entropies = Vec::new();
-        let mut guesses = 0;
+// Solver used to generate training/testing data
+fn load_worker_solver(source: &SolverSource) -> WordleSolver {
+    load_solver(source, Policy::MaximizeEntropy, Vec::new())
+}
 
-        loop {
-            entropies.push((solver.current_possibilities.len() as f64).log2());
-            solver.step();
-            guesses += 1;
+/// A shard open for appending. Dropping it flushes whatever is still buffered, so rows
+/// written before an early return or a panic reach the file.
+struct ShardWriter {
+    writer: BufWriter<File>,
+    unit: EntropyUnit, // Unit the entropies are written in
+}
 
-            let guess = solver.current_guess.unwrap();
-            let feedback = guess.match_result(&secret);
-            solver.guess(|_| feedback);
+impl ShardWriter {
+    /// Open `path` for appending entropies in `unit`, writing the header if the shard is
+    /// new. A shard from before the `solved` column is upgraded first (see
+    /// `upgrade_legacy_shard`); an existing shard in another unit is an error rather than
+    /// mixing units.
+    fn open(path: &str, unit: EntropyUnit) -> io::Result<ShardWriter> {
+        let header = unit.shard_header();
+        if upgrade_legacy_shard(Path::new(path))? {
+            note!("Upgraded {path} to the current shard format");
+        }
+        let existing = std::fs::read_to_string(path).unwrap_or_default();
+        if let Some(found) = existing.lines().next()
+            && found.trim() != header
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{path} starts '{}', not '{header}'", found.trim()),
+            ));
+        }
 
-            if solver.current_possibilities.len() == 1 || guesses == 6 {
-                break;
-            }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let is_new = file.metadata()?.len() == 0;
+        let mut shard = ShardWriter {
+            writer: BufWriter::new(file),
+            unit,
+        };
+        if is_new {
+            writeln!(shard.writer, "{}", header)?;
         }
-        let total = guesses as i32;
+        Ok(shard)
+    }
+
+    /// Append the rows of one finished game, with `entropies` in bits, and flush them, so
+    /// the shard only ever ends in whole games. `solved` is false for a game that ran out of
+    /// guesses.
+    fn write_game(
+        &mut self,
+        secret_idx: usize,
+        entropies: &[f64],
+        guesses: usize,
+        solved: bool,
+    ) -> io::Result<()> {
         for (step, &e) in entropies.iter().enumerate() {
-            writeln!(writer, "{},{},{}", secret_idx, e, total - step as i32).unwrap();
+            writeln!(
+                self.writer,
+                "{},{},{},{}",
+                secret_idx,
+                self.unit.from_bits(e),
+                guesses - step,
+                u8::from(solved)
+            )?;
+        }
+        self.writer.flush()
+    }
+}
+
+impl Drop for ShardWriter {
+    fn drop(&mut self) {
+        if let Err(e) = self.writer.flush() {
+            note!("⚠️  Could not flush shard: {e}");
+        }
+    }
+}
+
+/// Solve this worker's share of the secrets and append the results to its shard. Stops at
+/// the first failed write; every game written before it is kept.
+fn run_shard(
+    solver: &mut WordleSolver,
+    kind: RunKind,
+    worker_id: usize,
+    total_workers: usize,
+    options: &WorkerOptions,
+    source: &SolverSource,
+) -> io::Result<()> {
+    std::fs::create_dir_all(kind.dir(source.output_dir))?;
+    solver.set_max_guesses(options.max_guesses);
+    apply_start_word(solver, options.start.as_deref());
+
+    let shard_name = kind.shard_name(source.output_dir, worker_id);
+    let mut shard = ShardWriter::open(&shard_name, source.entropy_unit)?;
+
+    // Resume an interrupted run: secrets already in this worker's shard are not replayed
+    let finished = shard_file_guess_counts(Path::new(&shard_name)).unwrap_or_default();
+    let secrets: Vec<usize> = options
+        .secrets(solver.dictionary.len())
+        .filter(|i| i % total_workers == worker_id && !finished.contains_key(i))
+        .collect();
+    if !finished.is_empty() {
+        note!(
+            "[{:?} worker {}] resuming: {} secrets already in {}",
+            kind,
+            worker_id,
+            finished.len(),
+            shard_name
+        );
+    }
+    let mut timer = StepTimer::new(source.timing);
+    let started = Instant::now();
+
+    for (done, &secret_idx) in secrets.iter().enumerate() {
+        if interrupted() {
+            note!(
+                "[{:?} worker {}] interrupted after {}/{} secrets",
+                kind,
+                worker_id,
+                done,
+                secrets.len()
+            );
+            break;
+        }
+        let secret = solver.dictionary[secret_idx].clone();
+        let game = solver.play_out_with(&secret, |solver| timer.time(|| solver.step()));
+
+        // Moves remaining are counted from each state up to and including the guess that
+        // solved the game, capped by the configured budget; a game that hit the budget is
+        // marked unsolved. A game the solver gave up on has no such count.
+        match game.error {
+            None => {
+                let entropies: Vec<f64> = game
+                    .turns
+                    .iter()
+                    .map(|turn| (turn.remaining as f64).log2())
+                    .collect();
+                shard.write_game(secret_idx, &entropies, game.guesses(), game.solved)?
+            }
+            Some(e) => note!(
+                "[{:?} worker {}] skipping secret {}: {e}",
+                kind,
+                worker_id,
+                secret_idx
+            ),
+        }
+
+        let done = done + 1;
+        if done % PROGRESS_INTERVAL == 0 || done == secrets.len() {
+            let elapsed = started.elapsed();
+            let eta = elapsed.mul_f64((secrets.len() - done) as f64 / done as f64);
+            note!(
+                "[{:?} worker {}] {}/{} secrets ({:.1}%), elapsed {}, ETA {}",
+                kind,
+                worker_id,
+                done,
+                secrets.len(),
+                100.0 * done as f64 / secrets.len() as f64,
+                format_duration(elapsed),
+                format_duration(eta)
+            );
         }
-        writer.flush().unwrap();
+    }
+    timer.report(&format!("{:?} worker {}", kind, worker_id));
+    Ok(())
+}
+
+// How many secrets a worker solves between progress reports
+const PROGRESS_INTERVAL: usize = 50;
+
+/// Format a duration as e.g. "1h02m03s", "4m05s" or "6s"
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (h, m, s) = (secs / 3600, (secs / 60) % 60, secs % 60);
+    if h > 0 {
+        format!("{h}h{m:02}m{s:02}s")
+    } else if m > 0 {
+        format!("{m}m{s:02}s")
+    } else {
+        format!("{s}s")
+    }
+}
+
+/// Wall-clock time of each `step()` call, when `--timing` is on; verbose output also prints
+/// each step's time as it happens
+struct StepTimer {
+    enabled: bool,
+    steps: usize,
+    total: Duration,
+    min: Duration,
+    max: Duration,
+}
+
+impl StepTimer {
+    fn new(enabled: bool) -> StepTimer {
+        StepTimer {
+            enabled,
+            steps: 0,
+            total: Duration::ZERO,
+            min: Duration::MAX,
+            max: Duration::ZERO,
+        }
+    }
+
+    /// Run `step` (a call to `step()`), timing it if enabled or verbose
+    fn time<T>(&mut self, step: impl FnOnce() -> T) -> T {
+        if !self.enabled && verbosity() < Verbosity::Verbose {
+            return step();
+        }
+        let started = Instant::now();
+        let result = step();
+        let elapsed = started.elapsed();
+        verbose!("⏱  step took {:.3} ms", elapsed.as_secs_f64() * 1000.0);
+        if !self.enabled {
+            return result;
+        }
+
+        self.steps += 1;
+        self.total += elapsed;
+        self.min = self.min.min(elapsed);
+        self.max = self.max.max(elapsed);
+        result
+    }
+
+    /// Print the step time statistics, labelled with `who`, if enabled
+    fn report(&self, who: &str) {
+        if !self.enabled {
+            return;
+        }
+        if self.steps == 0 {
+            eprintln!("⏱  {who}: no steps timed");
+            return;
+        }
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        eprintln!(
+            "⏱  {who}: {} steps, min {:.3} ms, mean {:.3} ms, max {:.3} ms, total {:.3} ms",
+            self.steps,
+            ms(self.min),
+            ms(self.total) / self.steps as f64,
+            ms(self.max),
+            ms(self.total)
+        );
+    }
+}
+
+/// Options shared by the interactive commands
+#[derive(Args, Clone, Debug)]
+struct PlayOptions {
+    /// Entropy bucket width of the expected-moves curve built from training data
+    #[arg(long, default_value_t = DEFAULT_BUCKET_WIDTH, value_parser = parse_positive_f64)]
+    bucket_width: f64,
+
+    /// List the remaining words and their probabilities when fewer than this many are left
+    #[arg(long, default_value_t = 10)]
+    show_candidates_below: usize,
+
+    /// Policy to play with (default: score if training data exists, otherwise entropy)
+    #[arg(long, value_enum)]
+    policy: Option<PolicyArg>,
+
+    /// Append each guess to this file as `word:pattern:remaining`, replayable with `resume`
+    #[arg(long)]
+    log: Option<PathBuf>,
+
+    /// Letters already revealed before the first guess, by position, `.` where unknown,
+    /// e.g. `S....`
+    #[arg(long, value_parser = parse_green_mask)]
+    green: Option<Constraints>,
+
+    /// Don't point out repeated letters in the suggested guess
+    #[arg(long)]
+    no_duplicate_hints: bool,
+}
+
+/// Options shared by `train`/`test` and forwarded to the worker processes they spawn
+#[derive(Args, Clone, Debug)]
+struct WorkerOptions {
+    /// Guess budget per game; a game stops after this many guesses even if unsolved
+    #[arg(long, default_value_t = 6, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    max_guesses: usize,
+
+    /// Number of secrets (taken from the start of the dictionary) to solve; 0 means all
+    #[arg(long, default_value_t = 1_500)]
+    max_secrets: usize,
+
+    /// Skip this many words at the start of the dictionary before taking the secrets, e.g.
+    /// `test --skip-secrets 1500` to validate the curve on words `train` never solved
+    #[arg(long, default_value_t = 0)]
+    skip_secrets: usize,
+
+    /// Open every game with this dictionary word instead of the computed best opener
+    #[arg(long)]
+    start: Option<String>,
+}
+
+impl WorkerOptions {
+    /// Re-encode the options as command-line arguments for a worker process
+    fn to_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "--max-guesses".to_string(),
+            self.max_guesses.to_string(),
+            "--max-secrets".to_string(),
+            self.max_secrets.to_string(),
+            "--skip-secrets".to_string(),
+            self.skip_secrets.to_string(),
+        ];
+        if let Some(word) = &self.start {
+            args.push("--start".to_string());
+            args.push(word.clone());
+        }
+        args
+    }
+
+    /// Dictionary indices of the secrets to solve in a dictionary of `dictionary_len` words
+    fn secrets(&self, dictionary_len: usize) -> Range<usize> {
+        let first = self.skip_secrets.min(dictionary_len);
+        match self.max_secrets {
+            0 => first..dictionary_len,
+            cap => first..first.saturating_add(cap).min(dictionary_len),
+        }
+    }
+}
+
+/// A word list `--lang` can select
+struct Language {
+    code: &'static str,
+    name: &'static str,
+    dictionary: &'static str,
+    alphabet: Option<&'static str>, // Letters the list is written in; A-Z when `None`
+}
+
+// Bundled word lists by language code. Add a list here, with its alphabet if it needs
+// letters beyond A-Z, to make it selectable with --lang.
+const LANGUAGES: &[Language] = &[Language {
+    code: "en",
+    name: "English",
+    dictionary: "./words_5_letters.txt",
+    alphabet: None,
+}];
+
+impl Language {
+    fn find(code: &str) -> Option<&'static Language> {
+        LANGUAGES.iter().find(|lang| lang.code == code)
+    }
+
+    // Value parser for --lang, listing the known codes
+    fn parser() -> clap::builder::PossibleValuesParser {
+        LANGUAGES
+            .iter()
+            .map(|lang| clap::builder::PossibleValue::new(lang.code).help(lang.name))
+            .collect::<Vec<_>>()
+            .into()
     }
 }
 
 #[derive(Parser)]
 #[command(author, version, about)]
 struct Cli {
+    /// Read solver options from a TOML file (keys named like the flags, e.g.
+    /// `guess-threshold = 2`); options given on the command line take precedence
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Print only results and errors: no progress, notes or warnings
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Also print the settings in use (policy, prior, search options) and each step's time
+    #[arg(short, long, global = true)]
+    verbose: bool,
+
+    /// Word list to solve with, one five-letter word per line, most common first; `#` starts
+    /// a comment
+    #[arg(long, global = true, default_value = "./words_5_letters.txt")]
+    dictionary: PathBuf,
+
+    /// Solve with a bundled word list and its alphabet instead of --dictionary; --alphabet
+    /// still overrides the alphabet
+    #[arg(long, global = true, conflicts_with = "dictionary", value_parser = Language::parser())]
+    lang: Option<String>,
+
+    /// Directory holding the word encodings cache
+    #[arg(long, global = true, default_value = "./.cache")]
+    cache_dir: PathBuf,
+
+    /// Always recompute word encodings instead of using the cache
+    #[arg(long, global = true)]
+    no_cache: bool,
+
+    /// Directory whose train/ and test/ subdirectories hold the shards `train` and `test`
+    /// write and the training curve is read from; separate runs need separate directories
+    #[arg(long, global = true, default_value = ".")]
+    output_dir: PathBuf,
+
+    /// Weight the prior by a `word,count` frequency file instead of dictionary order
+    #[arg(long, global = true)]
+    frequencies: Option<PathBuf>,
+
+    /// Weight the prior in proportion to real frequencies from a `word,zipf` file (Zipf
+    /// scale, as from wordfreq); leave out to keep the sigmoid over dictionary order
+    #[arg(long, global = true, conflicts_with = "frequencies")]
+    zipf: Option<PathBuf>,
+
+    /// Weight the prior by a `word,weight` file of hand-tuned weights, used as is
+    #[arg(long, global = true, conflicts_with_all = ["frequencies", "zipf"])]
+    weights: Option<PathBuf>,
+
+    /// Weight of words missing from the --weights file
+    #[arg(long, global = true, default_value_t = FREQUENCY_FLOOR_WEIGHT, value_parser = parse_positive_f64, requires = "weights")]
+    default_weight: f64,
+
+    /// File of likely answers (one per line), e.g. NYT's answer list; on top of the prior,
+    /// every other dictionary word becomes a near-impossible answer
+    #[arg(long, global = true)]
+    answers: Option<PathBuf>,
+
+    /// Letters words may use, in order (default A-Z), e.g. "ABCDEFGHIJKLMNÑOPQRSTUVWXYZ"
+    #[arg(long, global = true)]
+    alphabet: Option<String>,
+
+    /// Evaluate every guess on every step instead of skipping ones that looked weak earlier in
+    /// the game (faster, but can occasionally miss the best guess)
+    #[arg(long, global = true)]
+    exact: bool,
+
+    /// Endgame search depth: 2 plans two guesses ahead once few words remain (slower)
+    #[arg(long, global = true, default_value_t = 1, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..=2))]
+    lookahead: usize,
+
+    /// Time every step() and print min/mean/max and total at the end of a session or run
+    #[arg(long, global = true)]
+    timing: bool,
+
+    /// Hard mode: only guess words that could still be the answer
+    #[arg(long, global = true)]
+    hard: bool,
+
+    /// Under --policy score, only guess words that could still be the answer once fewer
+    /// than this many bits of entropy (log2 of the possibilities left) remain; 0 = never
+    #[arg(long, global = true, default_value_t = 0.0, value_parser = parse_non_negative_f64)]
+    guess_threshold: f64,
+
+    /// Under --policy score, guess a word that could be the answer without searching once
+    /// its prior probability exceeds this; 1 = never
+    #[arg(long, global = true, default_value_t = COMMIT_THRESHOLD, value_parser = parse_probability)]
+    commit_threshold: f64,
+
+    /// Under --policy blend, bits of entropy one expected guess is worth giving up
+    #[arg(long, global = true, default_value_t = DEFAULT_LAMBDA, value_parser = parse_non_negative_f64)]
+    lambda: f64,
+
+    /// Unit to print entropies and write training shards in; thresholds, --lambda and
+    /// --bucket-width stay in bits
+    #[arg(long, global = true, default_value_t = EntropyUnit::Bits, value_parser = clap::builder::PossibleValuesParser::new(["bits", "nats", "digits"]).map(|unit| unit.parse::<EntropyUnit>().unwrap()))]
+    entropy_unit: EntropyUnit,
+
     #[command(subcommand)]
     cmd: Cmd,
 }
 
+// Value parser for --green: five letters or dots (or underscores), as the positional
+// term of `parse_constraints`
+fn parse_green_mask(mask: &str) -> Result<Constraints, String> {
+    if mask.chars().count() != 5
+        || !mask
+            .chars()
+            .all(|c| c.is_alphabetic() || c == '.' || c == '_')
+    {
+        return Err(format!(
+            "expected five letters or dots, e.g. S...., got '{}'",
+            mask
+        ));
+    }
+    parse_constraints(mask)
+}
+
+// Value parser for options that must be a finite number of 0 or more
+fn parse_non_negative_f64(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(v) if v.is_finite() && v >= 0.0 => Ok(v),
+        Ok(_) => Err("must be a number of 0 or more".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// Value parser for options that must be a probability
+fn parse_probability(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(v) if (0.0..=1.0).contains(&v) => Ok(v),
+        Ok(_) => Err("must be a probability between 0 and 1".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// Value parser for options that must be a finite number above zero
+fn parse_positive_f64(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(v) if v.is_finite() && v > 0.0 => Ok(v),
+        Ok(_) => Err("must be a number greater than 0".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Options read from a `--config` file. Keys are the global flags' names, plus the
+/// `policy` of commands that take one, e.g.
+///
+/// ```toml
+/// dictionary = "words/nyt.txt"
+/// answers = "words/nyt_answers.txt"
+/// hard = true
+/// policy = "score"
+/// guess-threshold = 2
+/// ```
+///
+/// Paths are relative to the current directory. Switches the file turns on (like `hard`)
+/// stay on, since the command line has no way to turn them off.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct ConfigFile {
+    dictionary: Option<PathBuf>,
+    lang: Option<String>,
+    cache_dir: Option<PathBuf>,
+    no_cache: Option<bool>,
+    output_dir: Option<PathBuf>,
+    frequencies: Option<PathBuf>,
+    zipf: Option<PathBuf>,
+    weights: Option<PathBuf>,
+    default_weight: Option<f64>,
+    answers: Option<PathBuf>,
+    alphabet: Option<String>,
+    exact: Option<bool>,
+    lookahead: Option<usize>,
+    timing: Option<bool>,
+    hard: Option<bool>,
+    guess_threshold: Option<f64>,
+    commit_threshold: Option<f64>,
+    lambda: Option<f64>,
+    entropy_unit: Option<EntropyUnit>,
+    policy: Option<PolicyArg>,
+}
+
+impl ConfigFile {
+    /// Read and validate `path`, exiting with a message if it can't be used
+    fn load(path: &Path) -> ConfigFile {
+        let parsed = std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| toml::from_str::<ConfigFile>(&text).map_err(|e| e.to_string()))
+            .and_then(ConfigFile::validate);
+        match parsed {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("❌  Invalid config file {}: {e}", path.display());
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // Apply the checks the equivalent flags' value parsers make
+    fn validate(self) -> Result<ConfigFile, String> {
+        let check = |key: &str, value: Option<f64>, parse: fn(&str) -> Result<f64, String>| {
+            value.map_or(Ok(()), |v| {
+                parse(&v.to_string())
+                    .map(|_| ())
+                    .map_err(|e| format!("{key}: {e}"))
+            })
+        };
+        check("default-weight", self.default_weight, parse_positive_f64)?;
+        check(
+            "guess-threshold",
+            self.guess_threshold,
+            parse_non_negative_f64,
+        )?;
+        check("commit-threshold", self.commit_threshold, parse_probability)?;
+        check("lambda", self.lambda, parse_non_negative_f64)?;
+        if let Some(code) = &self.lang {
+            if Language::find(code).is_none() {
+                return Err(format!("lang: unknown language '{code}'"));
+            }
+            if self.dictionary.is_some() {
+                return Err("lang and dictionary can't both be set".to_string());
+            }
+        }
+        if let Some(depth) = self.lookahead
+            && !(1..=2).contains(&depth)
+        {
+            return Err(format!("lookahead: {depth} is not in 1..=2"));
+        }
+        Ok(self)
+    }
+
+    /// Fill in every option of `cli` that `matches` shows wasn't given on the command line.
+    /// The prior files are taken as a group, so a prior chosen on the command line replaces
+    /// the file's rather than conflicting with it, and so are the dictionary and language.
+    fn apply(self, cli: &mut Cli, matches: &ArgMatches) {
+        let from_command_line =
+            |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+        macro_rules! fill {
+            ($($field:ident),*) => {
+                $(if let Some(value) = self.$field
+                    && !from_command_line(stringify!($field))
+                {
+                    cli.$field = value.into();
+                })*
+            };
+        }
+        fill!(
+            cache_dir,
+            no_cache,
+            output_dir,
+            default_weight,
+            answers,
+            alphabet,
+            exact,
+            lookahead,
+            timing,
+            hard,
+            guess_threshold,
+            commit_threshold,
+            lambda,
+            entropy_unit
+        );
+        if !["dictionary", "lang"]
+            .iter()
+            .any(|id| from_command_line(id))
+        {
+            cli.dictionary = self.dictionary.unwrap_or(cli.dictionary.clone());
+            cli.lang = self.lang.or(cli.lang.take());
+        }
+        if !["frequencies", "zipf", "weights"]
+            .iter()
+            .any(|id| from_command_line(id))
+        {
+            cli.frequencies = self.frequencies.or(cli.frequencies.take());
+            cli.zipf = self.zipf.or(cli.zipf.take());
+            cli.weights = self.weights.or(cli.weights.take());
+        }
+
+        let command_line_policy = matches
+            .subcommand()
+            .is_some_and(|(_, sub)| sub.value_source("policy") == Some(ValueSource::CommandLine));
+        if let Some(policy) = self.policy
+            && !command_line_policy
+        {
+            cli.cmd.set_policy(policy);
+        }
+    }
+}
+
+/// Solver policies selectable from the command line
+#[derive(Clone, Copy, Debug, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+enum PolicyArg {
+    /// Maximize the expected information of each guess
+    Entropy,
+    /// Minimize the expected number of guesses using the training curve
+    Score,
+    /// Minimize the number of words left by the least helpful feedback
+    WorstCase,
+    /// Maximize entropy minus --lambda times the expected number of guesses
+    Blend,
+}
+
 #[derive(Subcommand)]
 enum Cmd {
     Train {
+        /// Number of workers, capped at the logical CPUs; 0 reads $WORDLE_WORKERS, or
+        /// runs one per CPU if it isn't set
         #[arg(short, long, default_value_t = 0)]
         workers: usize,
+        /// Run workers as threads in this process instead of subprocesses
+        #[arg(long)]
+        threads: bool,
+        #[command(flatten)]
+        options: WorkerOptions,
     },
     Test {
+        /// Number of workers, capped at the logical CPUs; 0 reads $WORDLE_WORKERS, or
+        /// runs one per CPU if it isn't set
         #[arg(short, long, default_value_t = 0)]
         workers: usize,
+        /// Run workers as threads in this process instead of subprocesses
+        #[arg(long)]
+        threads: bool,
+        #[command(flatten)]
+        options: WorkerOptions,
     },
     TrainWorker {
         worker_id: usize,
         total_workers: usize,
+        #[command(flatten)]
+        options: WorkerOptions,
     },
     TestWorker {
         worker_id: usize,
         total_workers: usize,
+        #[command(flatten)]
+        options: WorkerOptions,
+    },
+    Play {
+        #[command(flatten)]
+        options: PlayOptions,
+        /// Open with this dictionary word instead of the computed best opener
+        #[arg(long)]
+        start: Option<String>,
+    },
+    /// Let the solver play against a secret of your choice, printing every turn
+    Auto {
+        /// The word to solve for; it must be in the dictionary
+        secret: String,
+        #[command(flatten)]
+        options: PlayOptions,
+        /// Open with this dictionary word instead of the computed best opener
+        #[arg(long)]
+        start: Option<String>,
+    },
+    /// Pick up a game in progress from its guesses so far, e.g. `resume crane:NPNPM`
+    Resume {
+        #[command(flatten)]
+        options: PlayOptions,
+        /// Guesses played so far as `word:pattern` (or `word:pattern:remaining`, as written
+        /// by --log); read from stdin when omitted
+        transcript: Vec<String>,
+    },
+    /// Advise on your own guesses: enter each word you played and its feedback
+    Assist {
+        #[command(flatten)]
+        options: PlayOptions,
+    },
+    /// Print every word still possible given guesses so far and/or constraints
+    Filter {
+        /// Guesses played so far as `word:pattern`
+        transcript: Vec<String>,
+        /// Extra constraints, e.g. "__E__ +AT -R"
+        #[arg(long)]
+        constrain: Option<String>,
+        /// Only print this many of the most likely words
+        #[arg(long)]
+        top: Option<usize>,
     },
-    Play,
+    /// Print the dictionary words containing the given letters in any position, e.g.
+    /// `anagram EAT --exclude RS`; five letters give their anagrams
+    Anagram {
+        /// Letters the words must contain; repeat a letter to require it more than once
+        letters: String,
+        /// Letters the words must not contain
+        #[arg(long, default_value = "")]
+        exclude: String,
+        /// Only print this many of the most common words
+        #[arg(long)]
+        top: Option<usize>,
+    },
+    /// Guess against an adversarial host that dodges your guesses for as long as it can,
+    /// like Absurdle
+    Absurdle,
+    /// Solve several boards at once, like Dordle (2) or Quordle (4)
+    Multi {
+        #[arg(long, default_value_t = 2, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        boards: usize,
+    },
+    /// Write the entropy → expected-moves curve from the training shards as CSV
+    Histogram {
+        #[arg(long, default_value_t = DEFAULT_BUCKET_WIDTH, value_parser = parse_positive_f64)]
+        bucket_width: f64,
+        /// Output file (default: stdout)
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Concatenate the training or testing shards into a single CSV with one header
+    Merge {
+        /// Which shards to merge
+        #[arg(long, value_enum, default_value_t = RunKind::Train)]
+        kind: RunKind,
+        /// Merged CSV file to write
+        #[arg(long)]
+        out: PathBuf,
+        /// Fail, writing nothing, if any shard row is malformed
+        #[arg(long)]
+        strict: bool,
+    },
+    /// Write the dictionary reordered most common first by a `word,count` frequency file,
+    /// as the rank prior expects; words the file doesn't list go at the end
+    SortDictionary {
+        /// `word,count` frequency file to rank the words by
+        #[arg(long)]
+        freq_file: PathBuf,
+        /// Sorted word list to write
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Print letter statistics and the most informative opener for the dictionary
+    Stats,
+    /// Report the dictionary words the configured prior practically never expects as answers
+    Coverage {
+        /// Prior below which a word counts as ignored
+        #[arg(long, default_value_t = 1e-6, value_parser = parse_positive_f64)]
+        epsilon: f64,
+        /// Number of ignored words to list
+        #[arg(long, default_value_t = 10)]
+        sample: usize,
+    },
+    /// Print the best opening guess under a policy and the configured prior
+    FirstGuess {
+        #[arg(long, value_enum, default_value_t = PolicyArg::Entropy)]
+        policy: PolicyArg,
+        /// Also list this many best openers, best first
+        #[arg(long)]
+        top: Option<usize>,
+    },
+    /// Write the solver's replies to every reachable feedback as a nested JSON tree
+    Tree {
+        /// Open with this dictionary word instead of the computed best opener
+        #[arg(long)]
+        start: Option<String>,
+        /// Number of guesses deep to enumerate
+        #[arg(long, default_value_t = 6, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        depth: usize,
+        /// Output JSON file
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Solve every word in the dictionary and report average guesses and success rate
+    Bench {
+        #[arg(long, value_enum, default_value_t = PolicyArg::Entropy)]
+        policy: PolicyArg,
+        /// Guess budget per game
+        #[arg(long, default_value_t = 6, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        max_guesses: usize,
+        /// Open every game with this dictionary word instead of the computed best opener
+        #[arg(long)]
+        start: Option<String>,
+    },
+    /// Solve every word in the dictionary under several policies and compare them in a table
+    Compare {
+        /// Policies to compare, comma separated
+        #[arg(long, value_enum, value_delimiter = ',', num_args = 1.., default_values = ["entropy", "score", "worst-case", "blend"])]
+        policies: Vec<PolicyArg>,
+        /// Guess budget per game
+        #[arg(long, default_value_t = 6, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        max_guesses: usize,
+        /// Open every game with this dictionary word instead of the computed best opener
+        #[arg(long)]
+        start: Option<String>,
+    },
+    /// Solve every word in the dictionary and report the one needing the most guesses
+    WorstCase {
+        #[arg(long, value_enum, default_value_t = PolicyArg::Entropy)]
+        policy: PolicyArg,
+        /// Guess budget to count overruns against (games are played to the end regardless)
+        #[arg(long, default_value_t = 6, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        max_guesses: usize,
+        /// Open every game with this dictionary word instead of the computed best opener
+        #[arg(long)]
+        start: Option<String>,
+    },
+    /// Solve randomly drawn secrets and report average guesses and success rate
+    Simulate {
+        /// Number of games to play
+        #[arg(long, default_value_t = 1000, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        games: usize,
+        /// Seed of the secret draw, to repeat a run (default: a random seed, printed)
+        #[arg(long)]
+        seed: Option<u64>,
+        #[arg(long, value_enum, default_value_t = PolicyArg::Entropy)]
+        policy: PolicyArg,
+        /// Guess budget per game
+        #[arg(long, default_value_t = 6, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        max_guesses: usize,
+        /// Open every game with this dictionary word instead of the computed best opener
+        #[arg(long)]
+        start: Option<String>,
+    },
+}
+
+impl Cmd {
+    /// Play with `policy`, if this command takes one
+    fn set_policy(&mut self, policy: PolicyArg) {
+        match self {
+            Cmd::Play { options, .. }
+            | Cmd::Auto { options, .. }
+            | Cmd::Resume { options, .. }
+            | Cmd::Assist { options } => options.policy = Some(policy),
+            Cmd::FirstGuess { policy: p, .. }
+            | Cmd::Bench { policy: p, .. }
+            | Cmd::WorstCase { policy: p, .. }
+            | Cmd::Simulate { policy: p, .. } => *p = policy,
+            _ => {}
+        }
+    }
 }
 
 fn main() {
-    match Cli::parse().cmd {
-        Cmd::Train { workers } => spawn_workers(workers, RunKind::Train),
-        Cmd::Test { workers } => spawn_workers(workers, RunKind::Test),
+    let matches = Cli::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    set_verbosity(match (cli.quiet, cli.verbose) {
+        (true, _) => Verbosity::Quiet,
+        (false, true) => Verbosity::Verbose,
+        (false, false) => Verbosity::Normal,
+    });
+    if let Some(path) = &cli.config {
+        ConfigFile::load(path).apply(&mut cli, &matches);
+    }
+    if let Some(lang) = cli.lang.as_deref().and_then(Language::find) {
+        cli.dictionary = lang.dictionary.into();
+        cli.alphabet = cli.alphabet.or(lang.alphabet.map(str::to_string));
+    }
+    let source = SolverSource {
+        cache_dir: (!cli.no_cache).then_some(cli.cache_dir.as_path()),
+        prior: match (&cli.frequencies, &cli.zipf, &cli.weights) {
+            (Some(path), _, _) => PriorConfig::Frequencies(path.clone()),
+            (None, Some(path), _) => PriorConfig::Zipf(path.clone()),
+            (None, None, Some(path)) => PriorConfig::Weights(path.clone(), cli.default_weight),
+            (None, None, None) => PriorConfig::Rank,
+        },
+        answers: cli.answers.clone(),
+        exact: cli.exact,
+        lookahead: cli.lookahead,
+        hard_mode: cli.hard,
+        guess_threshold: cli.guess_threshold,
+        commit_threshold: cli.commit_threshold,
+        entropy_unit: cli.entropy_unit,
+        timing: cli.timing,
+        lambda: cli.lambda,
+        output_dir: &cli.output_dir,
+        ..SolverSource::new(&cli.dictionary, cli.alphabet.as_deref())
+    };
+
+    match &cli.cmd {
+        Cmd::Train {
+            workers,
+            threads,
+            options,
+        } => spawn_workers(*workers, RunKind::Train, options, *threads, &source),
+        Cmd::Test {
+            workers,
+            threads,
+            options,
+        } => spawn_workers(*workers, RunKind::Test, options, *threads, &source),
         Cmd::TrainWorker {
             worker_id,
             total_workers,
-        } => run_generic_worker(RunKind::Train, worker_id, total_workers),
+            options,
+        } => run_generic_worker(RunKind::Train, *worker_id, *total_workers, options, &source),
         Cmd::TestWorker {
             worker_id,
             total_workers,
-        } => run_generic_worker(RunKind::Test, worker_id, total_workers),
-        Cmd::Play => interactive_play(),
+            options,
+        } => run_generic_worker(RunKind::Test, *worker_id, *total_workers, options, &source),
+        Cmd::Play { options, start } => interactive_play(&source, options, start.as_deref()),
+        Cmd::Auto {
+            secret,
+            options,
+            start,
+        } => auto_play(&source, options, secret, start.as_deref()),
+        Cmd::Resume {
+            options,
+            transcript,
+        } => resume_play(&source, options, transcript),
+        Cmd::Assist { options } => assist_play(&source, options),
+        Cmd::Filter {
+            transcript,
+            constrain,
+            top,
+        } => filter_words(&source, transcript, constrain.as_deref(), *top),
+        Cmd::Anagram {
+            letters,
+            exclude,
+            top,
+        } => print_words_containing(&source, letters, exclude, *top),
+        Cmd::Absurdle => absurdle_play(&source),
+        Cmd::Multi { boards } => multi_play(&source, *boards),
+        Cmd::Histogram { bucket_width, out } => {
+            export_histogram(*bucket_width, out.as_deref(), &source)
+        }
+        Cmd::Merge { kind, out, strict } => {
+            merge_shard_files(*kind, source.output_dir, out, *strict)
+        }
+        Cmd::SortDictionary { freq_file, out } => {
+            sort_dictionary_file(&source.dictionary, freq_file, out)
+        }
+        Cmd::Stats => print_dictionary_stats(&source),
+        Cmd::Coverage { epsilon, sample } => print_prior_coverage(&source, *epsilon, *sample),
+        Cmd::FirstGuess { policy, top } => print_first_guess(*policy, *top, &source),
+        Cmd::Tree { start, depth, out } => {
+            export_decision_tree(&source, start.as_deref(), *depth, out)
+        }
+        Cmd::Bench {
+            policy,
+            max_guesses,
+            start,
+        } => run_benchmark(*policy, *max_guesses, start.as_deref(), &source),
+        Cmd::Compare {
+            policies,
+            max_guesses,
+            start,
+        } => run_comparison(policies, *max_guesses, start.as_deref(), &source),
+        Cmd::WorstCase {
+            policy,
+            max_guesses,
+            start,
+        } => run_worst_case(*policy, *max_guesses, start.as_deref(), &source),
+        Cmd::Simulate {
+            games,
+            seed,
+            policy,
+            max_guesses,
+            start,
+        } => run_simulation(
+            *games,
+            *seed,
+            *policy,
+            *max_guesses,
+            start.as_deref(),
+            &source,
+        ),
     }
 }
@@ -0,0 +1,89 @@
+//! Regression test: the solver's full sequence of guesses for a few secrets, under fixed
+//! policies and the default prior, must match the committed transcript in `tests/data`.
+//!
+//! After an intended change of behaviour, regenerate the transcript with
+//! `UPDATE_GOLDEN=1 cargo test --test golden` and review the diff.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use wordle_solver::{Policy, SolverConfig, WordEncoding, WordleSolver, format_feedback, is_solved};
+
+// GAMES, NEEDS and STUDY are solved differently under the score policy than by entropy
+const SECRETS: [&str; 11] = [
+    "their", "which", "about", "class", "water", "eight", "young", "small", "games", "needs",
+    "study",
+];
+
+// On the fixture's most common words alone, the worst-case policy opens differently from
+// the other two, so its section pins its own choices too
+const TOP_WORDS: usize = 100;
+const TOP_SECRETS: [&str; 6] = ["their", "which", "about", "water", "young", "small"];
+
+fn fixture_words() -> Vec<String> {
+    fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data/words.txt"))
+        .expect("fixture dictionary")
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+fn fixture_solver(words: &[String], policy: Policy) -> WordleSolver {
+    SolverConfig::from_words(words.to_vec())
+        .policy(policy)
+        .build()
+        .expect("fixture solver")
+}
+
+// One line per game: the secret, then each guess with the feedback it got
+fn transcript(name: &str, policy: Policy, words: &[String], secrets: &[&str]) -> String {
+    let mut out = String::new();
+    for secret in secrets {
+        let mut solver = fixture_solver(words, policy);
+        let secret = WordEncoding::from_string(secret, solver.alphabet()).unwrap();
+        write!(out, "{name} {secret}:").unwrap();
+        for _ in 0..solver.dictionary.len() {
+            let guess = solver.step().expect("a guess while the secret is possible");
+            let feedback = guess.match_result(&secret);
+            write!(out, " {guess}/{}", format_feedback(&feedback)).unwrap();
+            if is_solved(&feedback) {
+                break;
+            }
+            solver
+                .guess(|_| feedback)
+                .expect("feedback from the secret");
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[test]
+fn suggested_guesses_match_golden_transcript() {
+    let words = fixture_words();
+    let top = &words[..TOP_WORDS];
+    let policies = [
+        ("entropy", Policy::MaximizeEntropy),
+        ("score", Policy::MinimizeScore),
+        ("worst-case", Policy::MinimizeWorstCase),
+    ];
+    let mut actual = String::new();
+    for (name, policy) in policies {
+        actual += &transcript(name, policy, &words, &SECRETS);
+    }
+    for (name, policy) in policies {
+        actual += &transcript(&format!("{name}/top"), policy, top, &TOP_SECRETS);
+    }
+
+    let golden = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data/golden.txt");
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&golden, &actual).unwrap();
+    }
+    let expected = fs::read_to_string(&golden).expect("golden transcript");
+    assert_eq!(
+        actual,
+        expected,
+        "solver guesses changed; see {}",
+        golden.display()
+    );
+}
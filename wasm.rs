@@ -0,0 +1,148 @@
+//! Browser bindings for the solver, enabled by the `wasm` feature.
+//!
+//! Everything here works on an in-memory word list supplied from JavaScript; no threads are
+//! spawned and the filesystem is never touched, so it compiles to `wasm32-unknown-unknown`.
+
+use crate::{Alphabet, SolverConfig, WordEncoding, WordleSolver, format_feedback, parse_feedback};
+use wasm_bindgen::prelude::*;
+
+/// A suggested guess together with the solver's view of it
+#[wasm_bindgen(getter_with_clone)]
+pub struct GuessSuggestion {
+    pub word: String,
+    pub entropy: f64,
+    pub expected_score: f64,
+    pub remaining: usize,
+}
+
+/// One guess of a game played by `play_out`
+#[wasm_bindgen(getter_with_clone)]
+pub struct GameTurn {
+    pub word: String,
+    pub pattern: String, // M/P/N feedback the guess got
+    pub remaining: usize,
+    pub entropy: f64,
+}
+
+#[wasm_bindgen]
+pub struct WasmSolver {
+    solver: WordleSolver,
+}
+
+#[wasm_bindgen]
+impl WasmSolver {
+    /// Build a solver from a JS array of five-letter words, ordered most common first.
+    /// `alphabet` lists the letters words may use, in order; it defaults to A-Z.
+    #[wasm_bindgen(constructor)]
+    pub fn new(words: Vec<String>, alphabet: Option<String>) -> Result<WasmSolver, JsError> {
+        let alphabet = match alphabet {
+            Some(letters) => Alphabet::new(&letters).map_err(|e| JsError::new(&e))?,
+            None => Alphabet::english(),
+        };
+        let solver = SolverConfig::from_words(words)
+            .alphabet(alphabet)
+            .build()
+            .map_err(|e| JsError::new(&e.to_string()))?;
+
+        Ok(WasmSolver { solver })
+    }
+
+    /// Compute the suggested next guess, or `undefined` if no guess is left.
+    pub fn next_guess(&mut self) -> Option<GuessSuggestion> {
+        let guess = self.solver.step().ok()?;
+
+        Some(GuessSuggestion {
+            word: guess.to_string(),
+            entropy: self.solver.current_guess_entropy,
+            expected_score: self.solver.current_expected_score,
+            remaining: self.solver.remaining_count(),
+        })
+    }
+
+    /// The guess `next_guess` would suggest now, without making it the current guess; e.g.
+    /// to preview a suggestion on hover. `undefined` if no guess is left.
+    pub fn peek_guess(&self) -> Option<GuessSuggestion> {
+        let info = self.solver.best_guess()?;
+
+        Some(GuessSuggestion {
+            word: info.word.to_string(),
+            entropy: info.entropy,
+            expected_score: info.expected_score,
+            remaining: self.solver.remaining_count(),
+        })
+    }
+
+    /// The `k` best guesses for the current state, best first, whether or not `next_guess`
+    /// has been called. Each is evaluated afresh, so this is slower than `next_guess`.
+    pub fn ranked_guesses(&self, k: usize) -> Vec<GuessSuggestion> {
+        let remaining = self.solver.remaining_count();
+        self.solver
+            .ranked_guesses(k)
+            .into_iter()
+            .map(|(word, entropy, expected_score)| GuessSuggestion {
+                word,
+                entropy,
+                expected_score,
+                remaining,
+            })
+            .collect()
+    }
+
+    /// Narrow the possibilities with the feedback for `guess`. `pattern` is five characters
+    /// of M/P/N, 2/1/0 or 🟩/🟨/⬛. The guess need not be the one suggested by `next_guess`.
+    pub fn apply_feedback(&mut self, guess: &str, pattern: &str) -> Result<(), JsError> {
+        let guess = WordEncoding::from_string(guess, self.solver.alphabet())
+            .map_err(|e| JsError::new(&e))?;
+        let feedback =
+            parse_feedback(&pattern.trim().to_uppercase()).map_err(|e| JsError::new(&e))?;
+
+        self.solver
+            .apply(&guess, feedback)
+            .map_err(|_| JsError::new("contradictory feedback: no words match"))
+    }
+
+    /// Keep only the words `other` also has left, pooling feedback two players got for
+    /// their own guesses. Both solvers must have been built from the same word list.
+    pub fn intersect(&mut self, other: &WasmSolver) -> Result<(), JsError> {
+        self.solver
+            .intersect(&other.solver)
+            .map_err(|e| JsError::new(&e.to_string()))
+    }
+
+    /// Start a new game and play it against `secret` until solved or out of guesses,
+    /// returning each guess. The game is lost if the last guess isn't all M.
+    pub fn play_out(&mut self, secret: &str) -> Result<Vec<GameTurn>, JsError> {
+        let secret = WordEncoding::from_string(secret, self.solver.alphabet())
+            .map_err(|e| JsError::new(&e))?;
+
+        let game = self.solver.play_out(&secret);
+        if let Some(e) = game.error {
+            return Err(JsError::new(&e.to_string()));
+        }
+        Ok(game
+            .turns
+            .into_iter()
+            .map(|turn| GameTurn {
+                word: turn.guess.to_string(),
+                pattern: format_feedback(&turn.pattern),
+                remaining: turn.remaining,
+                entropy: turn.entropy,
+            })
+            .collect())
+    }
+
+    /// Number of words still consistent with the feedback so far
+    pub fn remaining(&self) -> usize {
+        self.solver.remaining_count()
+    }
+
+    /// The words still consistent with the feedback so far, in dictionary order
+    pub fn candidates(&self) -> Vec<String> {
+        self.solver.candidates()
+    }
+
+    /// Start a new game with the same word list
+    pub fn reset(&mut self) {
+        self.solver.reset();
+    }
+}